@@ -1,18 +1,86 @@
 use darling::ast::Generics;
 use darling::{ast, util};
-use darling::{FromDeriveInput, FromField};
-use proc_macro2::{Group, Ident, TokenStream, TokenTree};
-use quote::{quote, ToTokens};
+use darling::{FromDeriveInput, FromField, FromMeta};
+use proc_macro2::{Group, Ident, Literal, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
 use std::collections::{HashMap, HashSet};
 use syn::__private::TokenStream2;
-use syn::{Attribute, Error, GenericArgument, GenericParam, PathArguments, Type};
+use syn::{
+    Attribute, Error, GenericArgument, GenericParam, PathArguments, RangeLimits, Type,
+    TypeParamBound, TypeTraitObject,
+};
 
 #[derive(Debug, FromDeriveInput)]
-#[darling(supports(struct_named), forward_attrs(allow, doc, cfg))]
+#[darling(
+    attributes(builder),
+    supports(struct_named),
+    forward_attrs(allow, doc, cfg)
+)]
 pub(crate) struct DefaultBuilderDeriveInput {
     pub(crate) ident: syn::Ident,
+    pub(crate) vis: syn::Visibility,
     pub(crate) data: ast::Data<util::Ignored, StructField>,
     pub(crate) generics: ast::Generics<syn::GenericParam>,
+
+    #[darling(default)]
+    pub(crate) into: bool,
+
+    #[darling(default)]
+    pub(crate) strip_option: bool,
+
+    #[darling(default)]
+    pub(crate) flag: bool,
+
+    #[darling(default)]
+    pub(crate) collection: bool,
+
+    #[darling(default)]
+    pub(crate) duration: bool,
+
+    #[darling(default)]
+    pub(crate) maybe: bool,
+
+    #[darling(default)]
+    pub(crate) update: bool,
+
+    #[darling(default)]
+    pub(crate) reset: bool,
+
+    #[darling(default)]
+    pub(crate) in_place: bool,
+
+    #[darling(default)]
+    pub(crate) with: bool,
+
+    #[darling(default)]
+    pub(crate) shared: bool,
+
+    #[darling(default)]
+    pub(crate) flattenable: bool,
+
+    #[darling(default)]
+    pub(crate) get: bool,
+
+    #[darling(default)]
+    pub(crate) get_mut: bool,
+
+    #[darling(default)]
+    pub(crate) on_set: Option<syn::Path>,
+
+    #[darling(multiple)]
+    pub(crate) setter_group: Vec<SetterGroup>,
+
+    #[darling(default)]
+    pub(crate) combinators: bool,
+
+    #[darling(default)]
+    pub(crate) apply_trait: Option<syn::Path>,
+}
+
+#[derive(Debug, FromMeta)]
+pub(crate) struct SetterGroup {
+    pub(crate) name: syn::Ident,
+    pub(crate) fields: util::PathList,
 }
 
 #[derive(Debug, FromField)]
@@ -23,7 +91,7 @@ pub(crate) struct StructField {
     pub(crate) attrs: Vec<syn::Attribute>,
 
     #[darling(default)]
-    pub(crate) into: bool,
+    pub(crate) into: Option<bool>,
 
     #[darling(default)]
     pub(crate) keep_outer: bool,
@@ -33,6 +101,313 @@ pub(crate) struct StructField {
 
     #[darling(default)]
     pub(crate) keep_type: bool,
+
+    #[darling(default)]
+    pub(crate) strip_option: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) collection: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) duration: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) each: Option<syn::Ident>,
+
+    #[darling(default)]
+    pub(crate) extend: Option<syn::Ident>,
+
+    #[darling(default)]
+    pub(crate) clear: Option<syn::Ident>,
+
+    #[darling(default)]
+    pub(crate) flag: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) maybe: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) update: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) reset: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) default: Option<DefaultValue>,
+
+    #[darling(default)]
+    pub(crate) in_place: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) with: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) get: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) get_mut: Option<bool>,
+
+    #[darling(default)]
+    pub(crate) get_copy: bool,
+
+    #[darling(default)]
+    pub(crate) validate: Option<syn::Path>,
+
+    #[darling(default)]
+    pub(crate) error: Option<syn::Path>,
+
+    #[darling(default)]
+    pub(crate) on_invalid: OnInvalid,
+
+    #[darling(default)]
+    pub(crate) range: Option<syn::Expr>,
+
+    #[darling(default)]
+    pub(crate) on_out_of_range: OnOutOfRange,
+
+    #[darling(default)]
+    pub(crate) group: Option<String>,
+
+    #[darling(default)]
+    pub(crate) exclusive: bool,
+
+    #[darling(default)]
+    pub(crate) nested: bool,
+
+    #[darling(default)]
+    pub(crate) flatten: Option<Flatten>,
+}
+
+impl StructField {
+    /// Whether the validated field only gets the `try_` setter and none of the panicking ones.
+    fn only_try_setter(&self) -> bool {
+        self.validate.is_some() && matches!(self.on_invalid, OnInvalid::Remove)
+    }
+}
+
+/// Value of `#[builder(flatten)]` or `#[builder(flatten = "prefix")]`.
+#[derive(Debug)]
+pub(crate) struct Flatten {
+    pub(crate) prefix: Option<syn::Ident>,
+}
+
+impl FromMeta for Flatten {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self { prefix: None })
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        let prefix = syn::parse_str(value).map_err(|_| darling::Error::unknown_value(value))?;
+
+        Ok(Self {
+            prefix: Some(prefix),
+        })
+    }
+}
+
+/// Value of `#[builder(default = ...)]`. In contrast to `syn::Expr` a string literal is kept as
+/// a literal instead of being parsed as an expression.
+#[derive(Debug)]
+pub(crate) struct DefaultValue(syn::Expr);
+
+impl FromMeta for DefaultValue {
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        Ok(Self(expr.clone()))
+    }
+}
+
+impl ToTokens for DefaultValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let expr = &self.0;
+
+        if let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(_),
+            ..
+        }) = expr
+        {
+            // so `"..."` works for `String` fields as well as for `&'static str` fields
+            tokens.extend(quote! { #expr.into() });
+        } else {
+            expr.to_tokens(tokens);
+        }
+    }
+}
+
+#[derive(Debug, Default, FromMeta)]
+pub(crate) enum OnInvalid {
+    #[default]
+    Panic,
+    Remove,
+}
+
+#[derive(Debug, Default, FromMeta)]
+pub(crate) enum OnOutOfRange {
+    Clamp,
+    #[default]
+    Panic,
+    DebugAssert,
+}
+
+/// How a value passed to a generated setter is converted into the field value.
+#[derive(Default)]
+struct Setter {
+    generics: TokenStream,
+    arg_type: TokenStream,
+    where_predicates: TokenStream,
+    value: TokenStream,
+    /// Non-generic variant of a generic setter. It is used where the argument is wrapped in an
+    /// `Option` because the generic type can't be inferred from `None`.
+    concrete: Option<Box<Setter>>,
+}
+
+impl Setter {
+    fn plain(ty: &Type) -> Self {
+        Self {
+            arg_type: ty.to_token_stream(),
+            value: quote! { value },
+            ..Default::default()
+        }
+    }
+
+    /// Wraps the value expression of this setter and of its non-generic variant.
+    fn map_value(self, f: &dyn Fn(&TokenStream) -> TokenStream) -> Self {
+        Self {
+            value: f(&self.value),
+            concrete: self
+                .concrete
+                .map(|concrete| Box::new(concrete.map_value(f))),
+            ..self
+        }
+    }
+
+    fn where_clause(&self) -> TokenStream {
+        let where_predicates = &self.where_predicates;
+
+        if where_predicates.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                where
+                    #where_predicates
+            }
+        }
+    }
+}
+
+impl DefaultBuilderDeriveInput {
+    fn fields(&self) -> Vec<&StructField> {
+        self.data
+            .as_ref()
+            .take_struct()
+            .expect("Is not enum")
+            .fields
+    }
+
+    fn group_fields(&self, group: &str) -> Vec<&StructField> {
+        self.fields()
+            .into_iter()
+            .filter(|f| f.group.as_deref() == Some(group))
+            .collect()
+    }
+
+    fn type_args(&self) -> TokenStream {
+        let type_args = self.generics.params.iter().map(|param| match param {
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        });
+
+        quote! { <#(#type_args),*> }
+    }
+
+    fn is_flag(&self, f: &StructField) -> bool {
+        let is_bool = matches!(&f.ty, Type::Path(path) if path.path.is_ident("bool"));
+
+        is_bool && f.flag.unwrap_or(self.flag)
+    }
+
+    fn after_set(&self, receiver: &TokenStream, name: &Ident) -> TokenStream {
+        self.after_set_with_type_args(receiver, name, &self.type_args())
+    }
+
+    /// Like `after_set` but for a receiver whose type args differ from the ones of the struct,
+    /// as is the case for setters that change the type of a generic field.
+    fn after_set_with_type_args(
+        &self,
+        receiver: &TokenStream,
+        name: &Ident,
+        type_args: &TokenStream,
+    ) -> TokenStream {
+        let field = self
+            .fields()
+            .into_iter()
+            .find(|f| f.ident.as_ref() == Some(name))
+            .expect("field exists");
+
+        let clear_group = field.group.as_ref().and_then(|group| {
+            let group_fields = self.group_fields(group);
+
+            if !group_fields.iter().any(|f| f.exclusive) {
+                return None;
+            }
+
+            let others = group_fields
+                .iter()
+                .filter(|f| f.ident.as_ref() != Some(name))
+                .map(|f| &f.ident);
+
+            Some(quote! {
+                if #receiver.#name.is_some() {
+                    #(#receiver.#others = None;)*
+                }
+            })
+        });
+
+        let on_set = self.on_set.as_ref().map(|on_set| {
+            let field_name = name.to_string();
+
+            // `Self` would be the wrong type inside of the generated traits
+            let on_set = match on_set.segments.first() {
+                Some(first) if on_set.leading_colon.is_none() && first.ident == "Self" => {
+                    let ident = &self.ident;
+                    let rest = on_set.segments.iter().skip(1);
+
+                    quote! { <#ident #type_args>#(::#rest)* }
+                }
+                _ => on_set.to_token_stream(),
+            };
+
+            quote! { #on_set(&mut #receiver, #field_name); }
+        });
+
+        quote! {
+            #clear_group
+            #on_set
+        }
+    }
+
+    fn with_after_set(&self, name: &Ident, value: TokenStream) -> TokenStream {
+        self.with_after_set_with_type_args(name, value, &self.type_args())
+    }
+
+    fn with_after_set_with_type_args(
+        &self,
+        name: &Ident,
+        value: TokenStream,
+        type_args: &TokenStream,
+    ) -> TokenStream {
+        let after_set = self.after_set_with_type_args(&quote! { this }, name, type_args);
+
+        if after_set.is_empty() {
+            value
+        } else {
+            quote! {
+                let mut this = #value;
+                #after_set
+                this
+            }
+        }
+    }
 }
 
 impl ToTokens for DefaultBuilderDeriveInput {
@@ -41,6 +416,9 @@ impl ToTokens for DefaultBuilderDeriveInput {
             ref ident,
             ref data,
             ref generics,
+            into: struct_into,
+            strip_option: struct_strip_option,
+            ..
         } = *self;
 
         let generic_idents: Vec<_> = generics.type_params().map(|t| &t.ident).collect();
@@ -53,6 +431,8 @@ impl ToTokens for DefaultBuilderDeriveInput {
         let fields = data.as_ref().take_struct().expect("Is not enum").fields;
 
         let mut methods = vec![];
+        let mut helper_methods = vec![];
+        let mut field_setters = vec![];
 
         let dot_dot_self = if fields.len() == 1 {
             quote! {}
@@ -63,6 +443,17 @@ impl ToTokens for DefaultBuilderDeriveInput {
         for f in fields.clone().into_iter() {
             let name = f.ident.as_ref().expect("named field");
 
+            if f.exclusive && f.group.is_none() {
+                tokens.extend(
+                    Error::new_spanned(
+                        &f.ident,
+                        "The `exclusive` option can only be used on fields of a `group`",
+                    )
+                    .to_compile_error(),
+                );
+                return;
+            }
+
             if f.skip {
                 continue;
             }
@@ -70,6 +461,14 @@ impl ToTokens for DefaultBuilderDeriveInput {
             let ty = &f.ty;
             let attrs = &f.attrs;
 
+            if let Err(err) = collection_methods(&mut helper_methods, self, f, name)
+                .and_then(|()| duration_methods(&mut helper_methods, self, f, name))
+            {
+                tokens.extend(err.to_compile_error());
+                return;
+            }
+            nested_methods(&mut helper_methods, self, f, name);
+
             let mut new_idents = vec![];
             let mut old_new_ident_tokens = vec![];
             let empty = HashSet::new();
@@ -88,7 +487,7 @@ impl ToTokens for DefaultBuilderDeriveInput {
                     ) {
                         generic_field = true;
 
-                        if f.into {
+                        if f.into == Some(true) {
                             tokens.extend(
                                 Error::new_spanned(&f.ident, "Fields that have struct generic types currently don't support the `into` option")
                                     .to_compile_error(),
@@ -96,6 +495,22 @@ impl ToTokens for DefaultBuilderDeriveInput {
                             return;
                         }
 
+                        if f.validate.is_some() {
+                            tokens.extend(
+                                Error::new_spanned(&f.ident, "Fields that have struct generic types currently don't support the `validate` option")
+                                    .to_compile_error(),
+                            );
+                            return;
+                        }
+
+                        if f.range.is_some() {
+                            tokens.extend(
+                                Error::new_spanned(&f.ident, "Fields that have struct generic types currently don't support the `range` option")
+                                    .to_compile_error(),
+                            );
+                            return;
+                        }
+
                         let (new_ident, new_ident_token) =
                             create_new_ident_and_token(generic_ident);
 
@@ -187,94 +602,446 @@ impl ToTokens for DefaultBuilderDeriveInput {
                         })
                         .collect();
 
+                    let mut replaced_type_args = self.type_args();
+
+                    for (old_ident_token, new_ident_token) in old_new_ident_tokens.iter() {
+                        replaced_type_args = replace_in_stream(
+                            &replaced_type_args.clone(),
+                            old_ident_token,
+                            new_ident_token,
+                        );
+                    }
+
+                    let body = self.with_after_set_with_type_args(
+                        name,
+                        quote! {
+                            #ident::#replaced_type_params {
+                                #name: value,
+                                #(#other_fields)*
+                            }
+                        },
+                        &replaced_type_args,
+                    );
+
                     methods.push(quote! {
                                 #(#attrs)*
                                 #[allow(non_camel_case_types)]
                                 pub fn #name<#(#new_idents),*>(self, value: #replaced_field_type) -> #ident #replaced_type_params
                                 #replaced_where_clause
                                 {
-                                    #ident::#replaced_type_params {
-                                        #name: value,
-                                        #(#other_fields)*
-                                    }
+                                    #body
                                 }
                             });
 
+                    if f.update.unwrap_or(self.update) {
+                        let update_name = format_ident!("update_{}", name);
+                        let update_doc = update_doc(name);
+                        let cfg_attrs = non_doc_attrs(attrs);
+                        let body = self.with_after_set_with_type_args(
+                            name,
+                            quote! {
+                                #ident::#replaced_type_params {
+                                    #name: f(self.#name),
+                                    #(#other_fields)*
+                                }
+                            },
+                            &replaced_type_args,
+                        );
+
+                        helper_methods.push(quote! {
+                            #[doc = #update_doc]
+                            #(#cfg_attrs)*
+                            #[allow(non_camel_case_types)]
+                            pub fn #update_name<#(#new_idents),*>(self, f: impl FnOnce(#ty) -> #replaced_field_type) -> #ident #replaced_type_params
+                            #replaced_where_clause
+                            {
+                                #body
+                            }
+                        });
+                    }
+
+                    field_setters.push((f, Setter::plain(ty)));
+
                     continue;
                 }
             }
 
-            let unwrap_inner_type = [
-                (get_inner_type(ty, "Box"), quote! { Box }),
-                (get_inner_type(ty, "Rc"), quote! { Rc }),
-                (get_inner_type(ty, "Arc"), quote! { Arc }),
-            ]
-            .into_iter()
-            .filter_map(|(ty, tok)| ty.map(|t| (t, tok)))
-            .next();
+            let unwrap_inner_type = get_wrapper_inner_type(ty);
 
             let option_inner_type = get_inner_type(ty, "Option");
 
-            if f.into {
-                if let Some(inner_type) = option_inner_type {
-                    methods.push(quote! {
-                        #(#attrs)*
-                        pub fn #name<OptionInnerType>(self, value: impl Into<Option<OptionInnerType>>) -> Self
-                        where
-                            OptionInnerType: Into<#inner_type>
-                        {
+            let option_unwrap_inner_type =
+                option_inner_type.as_ref().and_then(get_wrapper_inner_type);
+
+            let into = f.into.unwrap_or_else(|| {
+                is_auto_into_type(ty)
+                    || struct_into
+                        && (f.keep_outer
+                            || unwrap_inner_type.is_none() && option_unwrap_inner_type.is_none())
+            });
+
+            if f.strip_option == Some(true) && option_inner_type.is_none() {
+                tokens.extend(
+                    Error::new_spanned(
+                        &f.ident,
+                        "The `strip_option` option can only be used on fields of type `Option`",
+                    )
+                    .to_compile_error(),
+                );
+                return;
+            }
+
+            let is_bool = matches!(ty, Type::Path(path) if path.path.is_ident("bool"));
+
+            if f.flag == Some(true) && !is_bool {
+                tokens.extend(
+                    Error::new_spanned(
+                        &f.ident,
+                        "The `flag` option can only be used on fields of type `bool`",
+                    )
+                    .to_compile_error(),
+                );
+                return;
+            }
+
+            // an explicit `strip_option` on the field wins over the un-wrapping of `Option<Box<T>>`
+            let strip_option = f.strip_option.unwrap_or(
+                struct_strip_option && (f.keep_outer || option_unwrap_inner_type.is_none()),
+            );
+
+            let flag = self.is_flag(f);
+
+            if flag && (f.validate.is_some() || f.range.is_some()) {
+                tokens.extend(
+                    Error::new_spanned(
+                        &f.ident,
+                        "The `flag` option can't be combined with `validate` or `range`",
+                    )
+                    .to_compile_error(),
+                );
+                return;
+            }
+
+            let range_check = match f.range.as_ref().map(|range| range_check(f, name, range)) {
+                Some(Ok(check)) => Some(check),
+                Some(Err(err)) => {
+                    tokens.extend(err.to_compile_error());
+                    return;
+                }
+                None => None,
+            };
+
+            // checks a value of the field type the same way as the setter checks its value
+            let checked_value = |value: TokenStream| {
+                let value = match &range_check {
+                    Some(check) => ranged_value(f, check, &value),
+                    None => value,
+                };
+
+                validated_value(f, name, &value)
+            };
+
+            let setter = if flag {
+                Setter::plain(ty)
+            } else if let Some(inner_type) = option_inner_type.as_ref().filter(|_| strip_option) {
+                if !f.only_try_setter() {
+                    let opt_name = format_ident!("{}_opt", name);
+                    let value = checked_value(quote! { value });
+                    let body = self.with_after_set(
+                        name,
+                        quote! {
                             Self {
-                                #name: value.into().map(|v| v.into()),
+                                #name: #value,
                                 #dot_dot_self
                             }
-                        }
-                    })
-                } else {
-                    methods.push(quote! {
+                        },
+                    );
+
+                    helper_methods.push(quote! {
                         #(#attrs)*
-                        pub fn #name(self, value: impl Into<#ty>) -> Self {
-                            Self {
-                                #name: value.into(),
-                                #dot_dot_self
-                            }
+                        pub fn #opt_name(self, value: #ty) -> Self {
+                            #body
                         }
-                    })
+                    });
                 }
-            } else if unwrap_inner_type.is_some() && !f.keep_outer {
-                let (inner_type, inner_type_token) = unwrap_inner_type.expect("just checked above");
 
-                auto_wrapper_method(
-                    &mut methods,
-                    &dot_dot_self,
+                Setter {
+                    arg_type: inner_type.to_token_stream(),
+                    value: quote! { Some(value) },
+                    ..Default::default()
+                }
+            } else if into {
+                if let Some(inner_type) = option_inner_type {
+                    Setter {
+                        generics: quote! { <OptionInnerType> },
+                        arg_type: quote! { impl Into<Option<OptionInnerType>> },
+                        where_predicates: quote! { OptionInnerType: Into<#inner_type> },
+                        value: quote! { value.into().map(|v| v.into()) },
+                        concrete: Some(Box::new(Setter {
+                            arg_type: inner_type.to_token_stream(),
+                            value: quote! { Some(value) },
+                            ..Default::default()
+                        })),
+                    }
+                } else {
+                    Setter {
+                        arg_type: quote! { impl Into<#ty> },
+                        value: quote! { value.into() },
+                        concrete: Some(Box::new(Setter::plain(ty))),
+                        ..Default::default()
+                    }
+                }
+            } else if let Some((inner_type, wrapped_value)) =
+                unwrap_inner_type.filter(|_| !f.keep_outer)
+            {
+                auto_wrapper_setter(inner_type, wrapped_value, ty, &|value| value)
+            } else if let Some((inner_type, wrapped_value)) =
+                option_unwrap_inner_type.filter(|_| !f.keep_outer)
+            {
+                let clear_name = format_ident!("clear_{}", name);
+                let clear_doc = format!(" Sets `{name}` to `None`.");
+                let cfg_attrs = non_doc_attrs(attrs);
+                let body = self.with_after_set(
                     name,
-                    attrs,
-                    inner_type,
-                    inner_type_token,
-                );
-            } else {
-                methods.push(quote! {
-                    #(#attrs)*
-                    pub fn #name(self, value: #ty) -> Self {
+                    quote! {
                         Self {
-                            #name: value,
+                            #name: None,
                             #dot_dot_self
                         }
+                    },
+                );
+
+                helper_methods.push(quote! {
+                    #[doc = #clear_doc]
+                    #(#cfg_attrs)*
+                    pub fn #clear_name(self) -> Self {
+                        #body
                     }
                 });
-            }
-        }
 
-        let where_clause = generics
-            .where_clause
-            .as_ref()
-            .map(|w| quote! { #w })
-            .unwrap_or(quote! {});
+                auto_wrapper_setter(
+                    inner_type,
+                    wrapped_value,
+                    option_inner_type.as_ref().expect("is an option"),
+                    &|value| quote! { Some(#value) },
+                )
+            } else {
+                Setter::plain(ty)
+            };
 
-        tokens.extend(quote! {
-            impl #type_params #ident #type_params #where_clause {
-                #(#methods)*
+            let setter = if let Some(check) = &range_check {
+                setter.map_value(&|value| ranged_value(f, check, value))
+            } else {
+                setter
+            };
+
+            let setter = if let Some(validate) = &f.validate {
+                validated_setter(&mut methods, self, f, name, validate, setter)
+            } else {
+                setter
+            };
+
+            if flag {
+                let no_name = format_ident!("no_{}", name);
+                let [set_body, unset_body] = [quote! { true }, quote! { false }].map(|value| {
+                    self.with_after_set(
+                        name,
+                        quote! {
+                            Self {
+                                #name: #value,
+                                #dot_dot_self
+                            }
+                        },
+                    )
+                });
+
+                methods.push(quote! {
+                    #(#attrs)*
+                    pub fn #name(self) -> Self {
+                        #set_body
+                    }
+
+                    #(#attrs)*
+                    pub fn #no_name(self) -> Self {
+                        #unset_body
+                    }
+                });
+            } else if !f.only_try_setter() {
+                let Setter {
+                    generics,
+                    arg_type,
+                    value,
+                    ..
+                } = &setter;
+                let where_clause = setter.where_clause();
+
+                let body = self.with_after_set(
+                    name,
+                    quote! {
+                        Self {
+                            #name: #value,
+                            #dot_dot_self
+                        }
+                    },
+                );
+
+                methods.push(quote! {
+                    #(#attrs)*
+                    pub fn #name #generics(self, value: #arg_type) -> Self
+                    #where_clause
+                    {
+                        #body
+                    }
+                });
+            }
+
+            if f.update.unwrap_or(self.update) && !f.only_try_setter() {
+                let update_name = format_ident!("update_{}", name);
+                let update_doc = update_doc(name);
+                let cfg_attrs = non_doc_attrs(attrs);
+                let after_set = self.after_set(&quote! { self }, name);
+                let value = checked_value(quote! { f(self.#name) });
+
+                helper_methods.push(quote! {
+                    #[doc = #update_doc]
+                    #(#cfg_attrs)*
+                    pub fn #update_name(mut self, f: impl FnOnce(#ty) -> #ty) -> Self {
+                        self.#name = #value;
+                        #after_set
+                        self
+                    }
+                });
+            }
+
+            field_setters.push((f, setter));
+        }
+
+        let mut groups: Vec<&String> = vec![];
+
+        for group in fields.iter().filter_map(|f| f.group.as_ref()) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+
+        for group in groups {
+            match group_kind_method(self, group) {
+                Ok(method) => helper_methods.push(method),
+                Err(err) => {
+                    tokens.extend(err.to_compile_error());
+                    return;
+                }
+            }
+        }
+
+        for group in self.setter_group.iter() {
+            match setter_group_method(self, group, &field_setters) {
+                Ok(method) => methods.push(method),
+                Err(err) => {
+                    tokens.extend(err.to_compile_error());
+                    return;
+                }
+            }
+        }
+
+        for f in fields.iter() {
+            getter_methods(&mut helper_methods, self, f);
+        }
+
+        for (f, setter) in field_setters.iter() {
+            let name = f.ident.as_ref().expect("named field");
+
+            setter_methods(&mut helper_methods, self, f, name, setter);
+        }
+
+        let where_clause = generics
+            .where_clause
+            .as_ref()
+            .map(|w| quote! { #w })
+            .unwrap_or(quote! {});
+
+        let combinator_methods = |vis: TokenStream| {
+            quote! {
+                /// Applies `f` to `self` if `cond` is `true`. Otherwise `self` is returned unchanged.
+                #vis fn when(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self {
+                    if cond {
+                        f(self)
+                    } else {
+                        self
+                    }
+                }
+
+                /// Applies `f` to `self`.
+                #vis fn apply(self, f: impl FnOnce(Self) -> Self) -> Self {
+                    f(self)
+                }
+            }
+        };
+
+        if self.combinators {
+            helper_methods.push(combinator_methods(quote! { pub }));
+        }
+
+        tokens.extend(quote! {
+            impl #type_params #ident #type_params #where_clause {
+                #(#methods)*
+
+                #(#helper_methods)*
             }
         });
+
+        if self.shared {
+            shared_trait(tokens, self, &field_setters);
+        }
+
+        if self.flattenable {
+            flatten_trait(tokens, self, &field_setters);
+        }
+
+        for f in fields.iter() {
+            let Some(flatten) = &f.flatten else {
+                continue;
+            };
+
+            let name = f.ident.as_ref().expect("named field");
+            let ty = &f.ty;
+
+            let Some((macro_path, trait_path)) = flatten_paths(ty) else {
+                tokens.extend(
+                    Error::new_spanned(
+                        ty,
+                        "`flatten` can only be used on fields whose type is a path to a struct",
+                    )
+                    .to_compile_error(),
+                );
+                continue;
+            };
+
+            let prefix = &flatten.prefix;
+            let after_set = self.after_set(&quote! { self }, name);
+
+            // the macro generated for the embedded struct adds its fields and calls `__flatten!`
+            tokens.extend(quote! {
+                #macro_path! {
+                    header [impl #type_params #ident #type_params #where_clause]
+                    field [#name]
+                    ty [#ty]
+                    trait_path [#trait_path]
+                    prefix [#prefix]
+                    after_set [#after_set]
+                }
+            });
+        }
+
+        if let Some(apply_trait) = &self.apply_trait {
+            let methods = combinator_methods(quote! {});
+
+            tokens.extend(quote! {
+                impl #type_params #apply_trait for #ident #type_params #where_clause {
+                    #methods
+                }
+            });
+        }
     }
 }
 
@@ -420,6 +1187,823 @@ fn stream_contains(s: &TokenStream, t: &TokenTree) -> bool {
     })
 }
 
+fn setter_methods(
+    methods: &mut Vec<TokenStream>,
+    input: &DefaultBuilderDeriveInput,
+    f: &StructField,
+    name: &Ident,
+    setter: &Setter,
+) {
+    let Setter {
+        generics,
+        arg_type,
+        where_predicates,
+        value,
+        ..
+    } = setter;
+    let where_clause = setter.where_clause();
+
+    let cfg_attrs = non_doc_attrs(&f.attrs);
+    let after_set = input.after_set(&quote! { self }, name);
+    let only_try_setter = f.only_try_setter();
+
+    if f.maybe.unwrap_or(input.maybe) && !only_try_setter {
+        let maybe_name = format_ident!("maybe_{}", name);
+        let maybe_doc =
+            format!(" Sets `{name}` if `value` is `Some`. Otherwise the current value is kept.");
+
+        // `None` doesn't tell the type of a generic argument so use the non-generic variant
+        let maybe_setter = setter.concrete.as_deref().unwrap_or(setter);
+        let Setter {
+            generics,
+            arg_type,
+            value,
+            ..
+        } = maybe_setter;
+        let where_clause = maybe_setter.where_clause();
+
+        methods.push(quote! {
+            #[doc = #maybe_doc]
+            #(#cfg_attrs)*
+            pub fn #maybe_name #generics(mut self, value: Option<#arg_type>) -> Self
+            #where_clause
+            {
+                if let Some(value) = value {
+                    self.#name = #value;
+                    #after_set
+                }
+                self
+            }
+        });
+    }
+
+    if f.in_place.unwrap_or(input.in_place) && !only_try_setter {
+        let set_name = format_ident!("set_{}", name);
+        let set_doc = format!(" Sets `{name}` in place.");
+        let after_set = input.after_set(&quote! { (*self) }, name);
+
+        methods.push(quote! {
+            #[doc = #set_doc]
+            #(#cfg_attrs)*
+            pub fn #set_name #generics(&mut self, value: #arg_type) -> &mut Self
+            #where_clause
+            {
+                self.#name = #value;
+                #after_set
+                self
+            }
+        });
+    }
+
+    if f.with.unwrap_or(input.with) && !only_try_setter {
+        let with_name = format_ident!("with_{}", name);
+        let with_doc = format!(" Returns a clone of `self` with `{name}` set to the given value.");
+        let new_after_set = input.after_set(&quote! { new }, name);
+
+        let where_clause = if where_predicates.is_empty() {
+            quote! {
+                where
+                    Self: Clone
+            }
+        } else {
+            quote! {
+                where
+                    #where_predicates,
+                    Self: Clone
+            }
+        };
+
+        methods.push(quote! {
+            #[doc = #with_doc]
+            #(#cfg_attrs)*
+            pub fn #with_name #generics(&self, value: #arg_type) -> Self
+            #where_clause
+            {
+                let mut new = self.clone();
+                new.#name = #value;
+                #new_after_set
+                new
+            }
+        });
+    }
+
+    if f.reset.unwrap_or(input.reset || f.default.is_some()) {
+        let reset_name = format_ident!("reset_{}", name);
+
+        let (default_value, where_clause) = if let Some(default) = &f.default {
+            (default.to_token_stream(), quote! {})
+        } else {
+            (
+                quote! { Self::default().#name },
+                quote! {
+                    where
+                        Self: Default
+                },
+            )
+        };
+
+        let reset_doc = format!(" Resets `{name}` to its default value.");
+
+        methods.push(quote! {
+            #[doc = #reset_doc]
+            #(#cfg_attrs)*
+            pub fn #reset_name(mut self) -> Self
+            #where_clause
+            {
+                self.#name = #default_value;
+                #after_set
+                self
+            }
+        });
+    }
+}
+
+fn shared_trait(
+    tokens: &mut TokenStream,
+    input: &DefaultBuilderDeriveInput,
+    field_setters: &[(&StructField, Setter)],
+) {
+    let DefaultBuilderDeriveInput {
+        ref ident,
+        ref vis,
+        ref generics,
+        ..
+    } = *input;
+
+    let trait_ident = format_ident!("{}SharedExt", ident);
+    let trait_doc = format!(
+        " Builder methods for `Rc<{ident}>` and `Arc<{ident}>` that use `make_mut` to modify the shared value."
+    );
+
+    let type_params = &generics.params;
+    let type_args = input.type_args();
+    let predicates = generics
+        .where_clause
+        .as_ref()
+        .map(|where_clause| where_clause.predicates.iter().collect())
+        .unwrap_or_else(Vec::new);
+
+    let mut declarations = vec![];
+    let mut implementations = vec![];
+
+    for (f, setter) in field_setters.iter().filter(|(f, _)| !f.only_try_setter()) {
+        let name = f.ident.as_ref().expect("named field");
+        let attrs = &f.attrs;
+        let cfg_attrs = non_doc_attrs(attrs);
+        let after_set = input.after_set(&quote! { (*this) }, name);
+
+        let Setter {
+            generics,
+            arg_type,
+            value,
+            ..
+        } = setter;
+        let where_clause = setter.where_clause();
+
+        if input.is_flag(f) {
+            let no_name = format_ident!("no_{}", name);
+
+            declarations.push(quote! {
+                #(#attrs)*
+                fn #name(self) -> Self;
+
+                #(#attrs)*
+                fn #no_name(self) -> Self;
+            });
+
+            for (method_name, value) in [(name, quote! { true }), (&no_name, quote! { false })] {
+                implementations.push(quote! {
+                    #(#cfg_attrs)*
+                    fn #method_name(mut self) -> Self {
+                        let this = Self::make_mut(&mut self);
+                        this.#name = #value;
+                        #after_set
+                        self
+                    }
+                });
+            }
+
+            continue;
+        }
+
+        declarations.push(quote! {
+            #(#attrs)*
+            fn #name #generics(self, value: #arg_type) -> Self
+            #where_clause;
+        });
+
+        implementations.push(quote! {
+            #(#cfg_attrs)*
+            fn #name #generics(mut self, value: #arg_type) -> Self
+            #where_clause
+            {
+                let this = Self::make_mut(&mut self);
+                this.#name = #value;
+                #after_set
+                self
+            }
+        });
+    }
+
+    tokens.extend(quote! {
+        #[doc = #trait_doc]
+        #vis trait #trait_ident <#(#type_params),*>
+        where
+            #(#predicates,)*
+        {
+            #(#declarations)*
+        }
+
+        impl <#(#type_params),*> #trait_ident #type_args for std::rc::Rc<#ident #type_args>
+        where
+            #(#predicates,)*
+            #ident #type_args: Clone,
+        {
+            #(#implementations)*
+        }
+
+        impl <#(#type_params),*> #trait_ident #type_args for std::sync::Arc<#ident #type_args>
+        where
+            #(#predicates,)*
+            #ident #type_args: Clone,
+        {
+            #(#implementations)*
+        }
+    });
+}
+
+fn flatten_trait(
+    tokens: &mut TokenStream,
+    input: &DefaultBuilderDeriveInput,
+    field_setters: &[(&StructField, Setter)],
+) {
+    let DefaultBuilderDeriveInput {
+        ref ident,
+        ref vis,
+        ref generics,
+        ..
+    } = *input;
+
+    let trait_ident = format_ident!("{}FlattenExt", ident);
+    let macro_ident = format_ident!("__default_struct_builder_flatten_{}", ident);
+    let trait_doc = format!(
+        " Sets the fields of `{ident}` for the forwarding setters of structs that embed it in a field marked with `#[builder(flatten)]`. `FIELD` is the index of the field."
+    );
+
+    let type_params = &generics.params;
+    let type_args = input.type_args();
+    let predicates = generics
+        .where_clause
+        .as_ref()
+        .map(|where_clause| where_clause.predicates.iter().collect())
+        .unwrap_or_else(Vec::new);
+
+    let mut impls = vec![];
+    let mut fields = vec![];
+
+    for (index, (f, setter)) in field_setters
+        .iter()
+        .filter(|(f, _)| !f.only_try_setter())
+        .enumerate()
+    {
+        let name = f.ident.as_ref().expect("named field");
+        let index = Literal::usize_unsuffixed(index);
+        let flag = input.is_flag(f);
+        let attrs = &f.attrs;
+        let cfg_attrs = non_doc_attrs(attrs);
+        let after_set = input.after_set(&quote! { (*self) }, name);
+
+        let Setter {
+            arg_type,
+            where_predicates,
+            value,
+            ..
+        } = setter;
+
+        // generics of the setter become part of the trait so they are constrained by the impl
+        let setter_generics = setter
+            .generics
+            .clone()
+            .into_iter()
+            .filter_map(|token| match token {
+                TokenTree::Ident(ident) => Some(ident),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let generics_arg = if setter_generics.is_empty() {
+            quote! {}
+        } else {
+            quote! { , (#(#setter_generics,)*) }
+        };
+
+        // an `impl Trait` argument becomes a type parameter of the impl
+        let mut arg_tokens = arg_type.clone().into_iter();
+        let (value_param, value_type, value_bound) = match arg_tokens.next() {
+            Some(TokenTree::Ident(first)) if first == "impl" => {
+                let bounds = arg_tokens.collect::<TokenStream>();
+
+                (
+                    quote! { FlattenValue, },
+                    quote! { FlattenValue },
+                    quote! { FlattenValue: #bounds, },
+                )
+            }
+            _ => (quote! {}, arg_type.clone(), quote! {}),
+        };
+
+        impls.push(quote! {
+            #(#cfg_attrs)*
+            impl <#(#type_params,)* #value_param #(#setter_generics),*> #trait_ident<#index, #value_type #generics_arg> for #ident #type_args
+            where
+                #(#predicates,)*
+                #value_bound
+                #where_predicates
+            {
+                fn flatten_set(&mut self, value: #value_type) {
+                    self.#name = #value;
+                    #after_set
+                }
+            }
+        });
+
+        fields.push(quote! { (#(#attrs)* #name #index #flag) });
+    }
+
+    let macro_vis = match vis {
+        // a `macro_rules!` macro can't be re-exported outside of the crate
+        syn::Visibility::Public(_) => quote! { pub(crate) },
+        _ => vis.to_token_stream(),
+    };
+
+    tokens.extend(quote! {
+        #[doc = #trait_doc]
+        #vis trait #trait_ident<const FIELD: usize, Value, Generics = ()> {
+            /// Sets the field with the index `FIELD` to `value`.
+            fn flatten_set(&mut self, value: Value);
+        }
+
+        #(#impls)*
+
+        #[doc(hidden)]
+        macro_rules! #macro_ident {
+            ($($args:tt)*) => {
+                ::default_struct_builder::__flatten! {
+                    $($args)*
+                    fields [#(#fields)*]
+                }
+            };
+        }
+
+        // unused unless the struct is flattened somewhere in the crate
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #macro_vis use #macro_ident as #ident;
+    });
+}
+
+/// Turns the type `path::to::Inner<T>` of a flattened field into the path of the macro
+/// `path::to::Inner` and the path of the trait `path::to::InnerFlattenExt` generated for it.
+fn flatten_paths(ty: &Type) -> Option<(syn::Path, syn::Path)> {
+    if let Type::Path(syn::TypePath { qself: None, path }) = ty {
+        let mut macro_path = path.clone();
+        macro_path.segments.last_mut()?.arguments = PathArguments::None;
+
+        let mut trait_path = macro_path.clone();
+        let last = trait_path.segments.last_mut()?;
+        last.ident = format_ident!("{}FlattenExt", last.ident);
+
+        Some((macro_path, trait_path))
+    } else {
+        None
+    }
+}
+
+fn setter_group_method(
+    input: &DefaultBuilderDeriveInput,
+    group: &SetterGroup,
+    field_setters: &[(&StructField, Setter)],
+) -> Result<TokenStream, Error> {
+    let mut generics = vec![];
+    let mut params = vec![];
+    let mut where_predicates = vec![];
+    let mut statements = vec![];
+
+    for path in group.fields.iter() {
+        let Some((f, setter)) = field_setters
+            .iter()
+            .find(|(f, _)| f.ident.as_ref().is_some_and(|ident| path.is_ident(ident)))
+        else {
+            return Err(Error::new_spanned(
+                path,
+                "Fields of a `setter_group` have to be non-skipped fields of the struct",
+            ));
+        };
+
+        let name = f.ident.as_ref().expect("named field");
+
+        if f.only_try_setter() {
+            return Err(Error::new_spanned(
+                path,
+                "Fields with `on_invalid = \"remove\"` can't be part of a `setter_group`",
+            ));
+        }
+
+        let mut arg_type = setter.arg_type.clone();
+        let mut predicates = setter.where_predicates.clone();
+
+        // make the generics of the single setters unique
+        for token in setter.generics.clone() {
+            if let TokenTree::Ident(generic) = token {
+                let (new_ident, new_ident_token) =
+                    create_new_ident_and_token(&format_ident!("{}_{}", generic, name));
+                let old_ident_token = TokenTree::Ident(generic);
+
+                arg_type = replace_in_stream(&arg_type, &old_ident_token, &new_ident_token);
+                predicates = replace_in_stream(&predicates, &old_ident_token, &new_ident_token);
+                generics.push(new_ident);
+            }
+        }
+
+        if !predicates.is_empty() {
+            where_predicates.push(predicates);
+        }
+
+        let value = &setter.value;
+        let after_set = input.after_set(&quote! { self }, name);
+
+        params.push(quote! { #name: #arg_type });
+        statements.push(quote! {
+            self.#name = {
+                let value = #name;
+                #value
+            };
+            #after_set
+        });
+    }
+
+    let group_name = &group.name;
+    let field_names = group
+        .fields
+        .iter()
+        .map(|path| format!("`{}`", path.to_token_stream()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let doc = format!(" Sets the fields {field_names} at once.");
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(non_camel_case_types)]
+        pub fn #group_name<#(#generics),*>(mut self, #(#params),*) -> Self
+        where
+            #(#where_predicates),*
+        {
+            #(#statements)*
+            self
+        }
+    })
+}
+
+fn group_kind_method(input: &DefaultBuilderDeriveInput, group: &str) -> Result<TokenStream, Error> {
+    let group_fields = input.group_fields(group);
+
+    if let Some(f) = group_fields
+        .iter()
+        .find(|f| get_inner_type(&f.ty, "Option").is_none())
+    {
+        return Err(Error::new_spanned(
+            &f.ident,
+            "Fields of a `group` have to be of type `Option`",
+        ));
+    }
+
+    let exclusive = group_fields.iter().any(|f| f.exclusive);
+
+    if let Some(f) = group_fields.iter().find(|f| f.exclusive != exclusive) {
+        return Err(Error::new_spanned(
+            &f.ident,
+            format!(
+                "Either all or none of the fields of the group `{group}` have to be `exclusive`"
+            ),
+        ));
+    }
+
+    let kind_name = syn::parse_str::<Ident>(&format!("{group}_kind"))?;
+    let kind_doc = format!(
+        " Returns the name of the first field of the group `{group}` that is set or `None` if none is set."
+    );
+
+    let names = group_fields.iter().map(|f| &f.ident);
+    let name_strings = group_fields
+        .iter()
+        .map(|f| f.ident.as_ref().expect("named field").to_string());
+
+    Ok(quote! {
+        #[doc = #kind_doc]
+        pub fn #kind_name(&self) -> Option<&'static str> {
+            #(
+                if self.#names.is_some() {
+                    return Some(#name_strings);
+                }
+            )*
+
+            None
+        }
+    })
+}
+
+fn getter_methods(
+    methods: &mut Vec<TokenStream>,
+    input: &DefaultBuilderDeriveInput,
+    f: &StructField,
+) {
+    let name = f.ident.as_ref().expect("named field");
+    let ty = &f.ty;
+    let attrs = &f.attrs;
+
+    if f.get_copy {
+        let get_name = format_ident!("get_{}", name);
+
+        methods.push(quote! {
+            #(#attrs)*
+            pub fn #get_name(&self) -> #ty {
+                self.#name
+            }
+        });
+    } else if f.get.unwrap_or(input.get) {
+        let get_name = format_ident!("get_{}", name);
+
+        methods.push(quote! {
+            #(#attrs)*
+            pub fn #get_name(&self) -> &#ty {
+                &self.#name
+            }
+        });
+    }
+
+    if f.get_mut.unwrap_or(input.get_mut) {
+        let get_mut_name = format_ident!("get_{}_mut", name);
+
+        methods.push(quote! {
+            #(#attrs)*
+            pub fn #get_mut_name(&mut self) -> &mut #ty {
+                &mut self.#name
+            }
+        });
+    }
+}
+
+fn update_doc(name: &Ident) -> String {
+    format!(" Replaces `{name}` with the result of calling `f` with the current value.")
+}
+
+fn collection_methods(
+    methods: &mut Vec<TokenStream>,
+    input: &DefaultBuilderDeriveInput,
+    f: &StructField,
+    name: &Ident,
+) -> Result<(), Error> {
+    let after_set = input.after_set(&quote! { self }, name);
+
+    // naming one of the methods implies that the collection methods are wanted
+    let requested =
+        f.collection == Some(true) || f.each.is_some() || f.extend.is_some() || f.clear.is_some();
+    let enabled = f.collection.unwrap_or(input.collection || requested);
+
+    if !enabled {
+        return Ok(());
+    }
+
+    let map_types = ["HashMap", "BTreeMap", "IndexMap"]
+        .into_iter()
+        .find_map(|map| match &get_type_arguments(&f.ty, map)?[..] {
+            [key_type, value_type, ..] => Some((key_type.clone(), value_type.clone())),
+            _ => None,
+        });
+
+    let (add_method, item_type) = if let Some((key_type, value_type)) = map_types {
+        let add_name = f
+            .each
+            .clone()
+            .unwrap_or_else(|| format_ident!("insert_{}", name));
+        let add_doc = format!(" Inserts a key-value pair into `{name}`.");
+
+        (
+            quote! {
+                #[doc = #add_doc]
+                pub fn #add_name(mut self, key: impl Into<#key_type>, value: impl Into<#value_type>) -> Self {
+                    self.#name.insert(key.into(), value.into());
+                    #after_set
+                    self
+                }
+            },
+            quote! { (#key_type, #value_type) },
+        )
+    } else if let Some((item_type, add_prefix, add_fn)) = [
+        ("Vec", "push", quote! { push }),
+        ("VecDeque", "push", quote! { push_back }),
+        ("HashSet", "insert", quote! { insert }),
+        ("BTreeSet", "insert", quote! { insert }),
+        ("IndexSet", "insert", quote! { insert }),
+    ]
+    .into_iter()
+    .find_map(|(collection, add_prefix, add_fn)| {
+        let item_type = get_type_arguments(&f.ty, collection)?.into_iter().next()?;
+        Some((item_type, add_prefix, add_fn))
+    }) {
+        let add_name = f
+            .each
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}_{}", add_prefix, name));
+        let add_doc = if add_prefix == "push" {
+            format!(" Appends an item to `{name}`.")
+        } else {
+            format!(" Adds an item to `{name}`.")
+        };
+
+        (
+            quote! {
+                #[doc = #add_doc]
+                pub fn #add_name(mut self, item: #item_type) -> Self {
+                    self.#name.#add_fn(item);
+                    #after_set
+                    self
+                }
+            },
+            item_type.to_token_stream(),
+        )
+    } else if requested {
+        return Err(Error::new_spanned(
+            &f.ident,
+            "The `collection`, `each`, `extend` and `clear` options can only be used on fields of a collection type like `Vec` or `HashMap`",
+        ));
+    } else {
+        return Ok(());
+    };
+
+    let extend_name = f
+        .extend
+        .clone()
+        .unwrap_or_else(|| format_ident!("extend_{}", name));
+    let clear_name = f
+        .clear
+        .clone()
+        .unwrap_or_else(|| format_ident!("clear_{}", name));
+
+    let extend_doc = format!(" Extends `{name}` with the contents of an iterator.");
+    let clear_doc = format!(" Removes all items from `{name}`.");
+    let cfg_attrs = non_doc_attrs(&f.attrs);
+
+    methods.push(quote! {
+        #(#cfg_attrs)*
+        #add_method
+
+        #[doc = #extend_doc]
+        #(#cfg_attrs)*
+        pub fn #extend_name(mut self, items: impl IntoIterator<Item = #item_type>) -> Self {
+            self.#name.extend(items);
+            #after_set
+            self
+        }
+
+        #[doc = #clear_doc]
+        #(#cfg_attrs)*
+        pub fn #clear_name(mut self) -> Self {
+            self.#name.clear();
+            #after_set
+            self
+        }
+    });
+
+    Ok(())
+}
+
+fn duration_methods(
+    methods: &mut Vec<TokenStream>,
+    input: &DefaultBuilderDeriveInput,
+    f: &StructField,
+    name: &Ident,
+) -> Result<(), Error> {
+    let after_set = input.after_set(&quote! { self }, name);
+
+    let is_duration = if let Type::Path(path) = &f.ty {
+        let path = path.path.to_token_stream().to_string().replace(' ', "");
+        path == "Duration" || path == "std::time::Duration" || path == "core::time::Duration"
+    } else {
+        false
+    };
+
+    if f.duration == Some(true) && !is_duration {
+        return Err(Error::new_spanned(
+            &f.ident,
+            "The `duration` option can only be used on fields of type `Duration`",
+        ));
+    }
+
+    if !is_duration || !f.duration.unwrap_or(input.duration) {
+        return Ok(());
+    }
+
+    let ms_name = format_ident!("{}_ms", name);
+    let secs_name = format_ident!("{}_secs", name);
+
+    let ms_doc = format!(" Sets `{name}` to the given number of milliseconds.");
+    let secs_doc = format!(
+        " Sets `{name}` to the given number of seconds.\n\n # Panics\n\n Panics if `value` is negative, not finite or overflows `Duration`."
+    );
+    let cfg_attrs = non_doc_attrs(&f.attrs);
+
+    methods.push(quote! {
+        #[doc = #ms_doc]
+        #(#cfg_attrs)*
+        pub fn #ms_name(mut self, value: u64) -> Self {
+            self.#name = std::time::Duration::from_millis(value);
+            #after_set
+            self
+        }
+
+        #[doc = #secs_doc]
+        #(#cfg_attrs)*
+        pub fn #secs_name(mut self, value: f64) -> Self {
+            self.#name = std::time::Duration::from_secs_f64(value);
+            #after_set
+            self
+        }
+    });
+
+    Ok(())
+}
+
+fn get_type_arguments(ty: &Type, outer_type: &str) -> Option<Vec<Type>> {
+    if let Type::Path(path) = ty {
+        if let Some(seg) = path.path.segments.last() {
+            if seg.ident == outer_type {
+                if let PathArguments::AngleBracketed(args) = &seg.arguments {
+                    return Some(
+                        args.args
+                            .iter()
+                            .filter_map(|arg| match arg {
+                                GenericArgument::Type(ty) => Some(ty.clone()),
+                                _ => None,
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
+    }
+
+    None
+}
+
+fn is_auto_into_type(ty: &Type) -> bool {
+    if let Type::Path(path) = ty {
+        if let Some(seg) = path.path.segments.last() {
+            return seg.ident == "Cow" || seg.ident == "PathBuf" || seg.ident == "OsString";
+        }
+    }
+
+    false
+}
+
+fn nested_methods(
+    methods: &mut Vec<TokenStream>,
+    input: &DefaultBuilderDeriveInput,
+    f: &StructField,
+    name: &Ident,
+) {
+    if !f.nested {
+        return;
+    }
+
+    let with_name = format_ident!("{}_with", name);
+    let cfg_attrs = non_doc_attrs(&f.attrs);
+    let after_set = input.after_set(&quote! { self }, name);
+
+    let (inner_ty, value, doc) = if let Some(inner_ty) = get_inner_type(&f.ty, "Option") {
+        (
+            inner_ty,
+            quote! { Some(f(self.#name.take().unwrap_or_default())) },
+            format!(" Configures `{name}` by calling `f` with the current value or the default value if it is `None`."),
+        )
+    } else {
+        (
+            f.ty.clone(),
+            quote! { f(self.#name) },
+            format!(" Configures `{name}` by calling `f` with the current value."),
+        )
+    };
+
+    methods.push(quote! {
+        #[doc = #doc]
+        #(#cfg_attrs)*
+        pub fn #with_name(mut self, f: impl FnOnce(#inner_ty) -> #inner_ty) -> Self {
+            self.#name = #value;
+            #after_set
+            self
+        }
+    });
+}
+
 fn get_inner_type(ty: &Type, outer_type_start: &str) -> Option<Type> {
     match ty {
         Type::Path(path) => {
@@ -439,28 +2023,250 @@ fn get_inner_type(ty: &Type, outer_type_start: &str) -> Option<Type> {
     }
 }
 
-fn auto_wrapper_method(
+fn get_wrapper_inner_type(ty: &Type) -> Option<(Type, TokenStream)> {
+    let (inner_type, wrapped_value) = get_any_wrapper_inner_type(ty)?;
+
+    // an unsized value can't be passed to the setter so the field is set as is
+    if is_unsized_type(&inner_type) {
+        None
+    } else {
+        Some((inner_type, wrapped_value))
+    }
+}
+
+fn get_any_wrapper_inner_type(ty: &Type) -> Option<(Type, TokenStream)> {
+    if let Some(pinned_type) = get_inner_type(ty, "Pin") {
+        let inner_type = get_inner_type(&pinned_type, "Box")?;
+        let box_path = type_path_without_arguments(&pinned_type);
+
+        return Some((inner_type, quote! { #box_path::pin(value) }));
+    }
+
+    let inner_type = ["Box", "Rc", "Arc"]
+        .into_iter()
+        .find_map(|outer_type| get_inner_type(ty, outer_type))?;
+    let outer_path = type_path_without_arguments(ty);
+
+    let cell_type = ["RefCell", "Cell", "Mutex", "RwLock"]
+        .into_iter()
+        .find_map(|cell_type| get_inner_type(&inner_type, cell_type));
+
+    if let Some(cell_inner_type) = cell_type {
+        let cell_path = type_path_without_arguments(&inner_type);
+
+        Some((
+            cell_inner_type,
+            quote! { #outer_path::new(#cell_path::new(value)) },
+        ))
+    } else {
+        Some((inner_type, quote! { #outer_path::new(value) }))
+    }
+}
+
+fn is_unsized_type(ty: &Type) -> bool {
+    match ty {
+        Type::Slice(_) => true,
+        Type::Path(path) => path.path.segments.last().is_some_and(|seg| {
+            ["str", "Path", "OsStr", "CStr"]
+                .into_iter()
+                .any(|unsized_type| seg.ident == unsized_type)
+        }),
+        _ => false,
+    }
+}
+
+fn type_path_without_arguments(ty: &Type) -> TokenStream {
+    if let Type::Path(path) = ty {
+        let mut path = path.path.clone();
+
+        if let Some(seg) = path.segments.last_mut() {
+            seg.arguments = PathArguments::None;
+        }
+
+        path.to_token_stream()
+    } else {
+        ty.to_token_stream()
+    }
+}
+
+fn non_doc_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("doc"))
+        .collect()
+}
+
+fn validated_setter(
     methods: &mut Vec<TokenStream>,
-    dot_dot_self: &TokenStream,
+    input: &DefaultBuilderDeriveInput,
+    f: &StructField,
     name: &Ident,
-    attrs: &Vec<Attribute>,
-    inner_type: Type,
-    wrapper_type: TokenStream,
-) {
-    let inner_type = if let Type::TraitObject(obj) = inner_type {
-        let bounds = obj.bounds;
-        quote! { impl #bounds + 'static }
-    } else {
-        inner_type.to_token_stream()
-    };
+    validate: &syn::Path,
+    setter: Setter,
+) -> Setter {
+    let where_clause = setter.where_clause();
+    let Setter {
+        generics,
+        arg_type,
+        value,
+        ..
+    } = &setter;
+
+    let try_name = format_ident!("try_{}", name);
+    let attrs = &f.attrs;
+    let error = f
+        .error
+        .as_ref()
+        .map(|error| error.to_token_stream())
+        .unwrap_or(quote! { String });
+    let after_set = input.after_set(&quote! { self }, name);
 
     methods.push(quote! {
         #(#attrs)*
-        pub fn #name(self, value: #inner_type) -> Self {
-            Self {
-                #name: #wrapper_type::new(value),
-                #dot_dot_self
-            }
+        pub fn #try_name #generics(mut self, value: #arg_type) -> Result<Self, #error>
+        #where_clause
+        {
+            let value = #value;
+            #validate(&value)?;
+            self.#name = value;
+            #after_set
+            Ok(self)
         }
     });
+
+    setter.map_value(&|value| validated_value(f, name, value))
+}
+
+/// Wraps an expression of the field type so that it panics if the validation of the field fails.
+fn validated_value(f: &StructField, name: &Ident, value: &TokenStream) -> TokenStream {
+    let Some(validate) = &f.validate else {
+        return value.clone();
+    };
+
+    let message = format!("invalid value for `{name}`");
+
+    quote! {
+        {
+            let value = #value;
+            #validate(&value).expect(#message);
+            value
+        }
+    }
+}
+
+/// Returns the statements that check or clamp `value` according to the `range` of the field.
+fn range_check(f: &StructField, name: &Ident, range: &syn::Expr) -> Result<TokenStream, Error> {
+    let syn::Expr::Range(range) = range else {
+        return Err(Error::new_spanned(
+            range,
+            "The `range` option has to be a range expression like `0.0..=1.0`",
+        ));
+    };
+
+    let check = match f.on_out_of_range {
+        OnOutOfRange::Clamp => {
+            if range.end.is_some() && matches!(range.limits, RangeLimits::HalfOpen(_)) {
+                return Err(Error::new_spanned(
+                    range,
+                    "Clamping to a range requires an inclusive range like `0.0..=1.0`",
+                ));
+            }
+
+            let clamp_start = range.start.as_ref().map(|start| {
+                quote! {
+                    let value = if value < #start { #start } else { value };
+                }
+            });
+            let clamp_end = range.end.as_ref().map(|end| {
+                quote! {
+                    let value = if value > #end { #end } else { value };
+                }
+            });
+
+            quote! {
+                #clamp_start
+                #clamp_end
+            }
+        }
+        OnOutOfRange::Panic | OnOutOfRange::DebugAssert => {
+            let assert = if matches!(f.on_out_of_range, OnOutOfRange::Panic) {
+                quote! { assert! }
+            } else {
+                quote! { debug_assert! }
+            };
+            let message = format!(
+                "value for `{name}` is out of range {}",
+                range.to_token_stream().to_string().replace(' ', "")
+            );
+
+            quote! {
+                #assert((#range).contains(&value), #message);
+            }
+        }
+    };
+
+    Ok(check)
+}
+
+/// Wraps an expression of the field type with the `check` returned by `range_check`.
+fn ranged_value(f: &StructField, check: &TokenStream, value: &TokenStream) -> TokenStream {
+    // for `Option` fields the range applies to the inner value
+    if get_inner_type(&f.ty, "Option").is_some() {
+        quote! {
+            #value.map(|value| {
+                #check
+                value
+            })
+        }
+    } else {
+        quote! {
+            {
+                let value = #value;
+                #check
+                value
+            }
+        }
+    }
+}
+
+/// `wrapper_type` is the type that `wrapped_value` evaluates to before it is turned into the
+/// field value by `field_value`.
+fn auto_wrapper_setter(
+    inner_type: Type,
+    wrapped_value: TokenStream,
+    wrapper_type: &Type,
+    field_value: &dyn Fn(TokenStream) -> TokenStream,
+) -> Setter {
+    if let Type::TraitObject(obj) = inner_type {
+        Setter {
+            arg_type: trait_object_to_impl_trait(&obj),
+            value: field_value(wrapped_value),
+            // the boxed trait object itself, so `None` can be passed to `maybe_`
+            concrete: Some(Box::new(Setter {
+                arg_type: wrapper_type.to_token_stream(),
+                value: field_value(quote! { value }),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+    } else {
+        Setter {
+            arg_type: inner_type.to_token_stream(),
+            value: field_value(wrapped_value),
+            ..Default::default()
+        }
+    }
+}
+
+fn trait_object_to_impl_trait(obj: &TypeTraitObject) -> TokenStream {
+    let (lifetimes, traits): (Vec<_>, Vec<_>) = obj
+        .bounds
+        .iter()
+        .partition(|bound| matches!(bound, TypeParamBound::Lifetime(_)));
+
+    if lifetimes.is_empty() {
+        quote! { impl #(#traits)+* + 'static }
+    } else {
+        quote! { impl #(#traits)+* + #(#lifetimes)+* }
+    }
 }
@@ -0,0 +1,152 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::{bracketed, parenthesized, Attribute, LitBool, LitInt, Path, Type};
+
+/// Input of the hidden `__flatten!` macro. It is assembled by the derive of the struct with the
+/// `#[builder(flatten)]` field and completed with the `fields` of the embedded struct by the
+/// macro that the derive of the embedded struct generates.
+pub(crate) struct FlattenInput {
+    impl_header: TokenStream,
+    field: Ident,
+    ty: Type,
+    trait_path: Path,
+    prefix: Option<Ident>,
+    after_set: TokenStream,
+    fields: Vec<FlattenedField>,
+}
+
+struct FlattenedField {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    index: LitInt,
+    flag: LitBool,
+}
+
+fn parse_bracketed_after(input: ParseStream, keyword: &str) -> syn::Result<TokenStream> {
+    let ident: Ident = input.parse()?;
+
+    if ident != keyword {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!("expected `{keyword}`"),
+        ));
+    }
+
+    let content;
+    bracketed!(content in input);
+
+    content.parse()
+}
+
+impl Parse for FlattenInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let impl_header = parse_bracketed_after(input, "header")?;
+        let field = syn::parse2(parse_bracketed_after(input, "field")?)?;
+        let ty = syn::parse2(parse_bracketed_after(input, "ty")?)?;
+        let trait_path = syn::parse2(parse_bracketed_after(input, "trait_path")?)?;
+        let prefix = syn::parse2(parse_bracketed_after(input, "prefix")?)?;
+        let after_set = parse_bracketed_after(input, "after_set")?;
+
+        let fields_ident: Ident = input.parse()?;
+
+        if fields_ident != "fields" {
+            return Err(syn::Error::new(fields_ident.span(), "expected `fields`"));
+        }
+
+        let content;
+        bracketed!(content in input);
+
+        let mut fields = vec![];
+
+        while !content.is_empty() {
+            let field;
+            parenthesized!(field in content);
+
+            fields.push(FlattenedField {
+                attrs: field.call(Attribute::parse_outer)?,
+                name: field.parse()?,
+                index: field.parse()?,
+                flag: field.parse()?,
+            });
+        }
+
+        Ok(Self {
+            impl_header,
+            field,
+            ty,
+            trait_path,
+            prefix,
+            after_set,
+            fields,
+        })
+    }
+}
+
+impl FlattenInput {
+    /// Generates the forwarding setters of all fields of the embedded struct.
+    pub(crate) fn forwarding_methods(&self) -> TokenStream {
+        let FlattenInput {
+            ref impl_header,
+            ref field,
+            ref ty,
+            ref trait_path,
+            ref prefix,
+            ref after_set,
+            ref fields,
+        } = *self;
+
+        let methods = fields.iter().map(|f| {
+            let FlattenedField {
+                ref attrs,
+                ref name,
+                ref index,
+                ref flag,
+            } = *f;
+
+            let method_name = match prefix {
+                Some(prefix) => format_ident!("{}_{}", prefix, name),
+                None => name.clone(),
+            };
+
+            // `self` has to be hygienic with the `after_set` tokens of the derive
+            if flag.value {
+                let no_method_name = format_ident!("no_{}", method_name);
+
+                quote_spanned! { field.span()=>
+                    #(#attrs)*
+                    pub fn #method_name(mut self) -> Self {
+                        <#ty as #trait_path<#index, bool>>::flatten_set(&mut self.#field, true);
+                        #after_set
+                        self
+                    }
+
+                    #(#attrs)*
+                    pub fn #no_method_name(mut self) -> Self {
+                        <#ty as #trait_path<#index, bool>>::flatten_set(&mut self.#field, false);
+                        #after_set
+                        self
+                    }
+                }
+            } else {
+                quote_spanned! { field.span()=>
+                    #(#attrs)*
+                    pub fn #method_name<FlattenValue, FlattenGenerics>(mut self, value: FlattenValue) -> Self
+                    where
+                        #ty: #trait_path<#index, FlattenValue, FlattenGenerics>,
+                    {
+                        <#ty as #trait_path<#index, FlattenValue, FlattenGenerics>>::flatten_set(&mut self.#field, value);
+                        #after_set
+                        self
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #impl_header {
+                #(#methods)*
+            }
+        }
+    }
+}
@@ -77,6 +77,994 @@
 //!
 //! All doc comments on fields are directly passed on to their generated setter methods.
 //!
+//! ### Struct level `into`
+//!
+//! Instead of repeating `#[builder(into)]` on every field it can be specified once on the struct.
+//! Then all fields behave as if they were annotated with `#[builder(into)]`. Single fields can opt
+//! out with `#[builder(into = false)]`. Fields with a struct generic type and fields that are
+//! automatically un-wrapped (see below) are excluded.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(into)]
+//! pub struct SomeOptions<T> {
+//!     name: String,
+//!     offset: Option<f64>,
+//!
+//!     #[builder(into = false)]
+//!     count: u32,
+//!
+//!     value: T,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::<u8>::default()
+//!     .name("Hello")
+//!     .offset(4.0)
+//!     .count(3)
+//!     .value("generic");
+//!
+//! assert_eq!(options.name, "Hello");
+//! assert_eq!(options.offset, Some(4.0));
+//! assert_eq!(options.count, 3);
+//! assert_eq!(options.value, "generic");
+//! # }
+//! ```
+//!
+//! ### Strip `Option`
+//!
+//! For fields of type `Option<T>` the builder method normally requires you to pass `Some(value)`.
+//! With `into` the method becomes generic which can get in the way of type inference, for example
+//! for numeric literals. Annotate the field with `#[builder(strip_option)]` to generate a method
+//! that accepts `T` directly and an additional method with the suffix `_opt` that accepts an
+//! `Option<T>`. Like `into` this can also be specified on the struct level and turned off for
+//! single fields with `#[builder(strip_option = false)]`.
+//!
+//! The struct level `strip_option` doesn't apply to fields of type `Option<Box<T>>`,
+//! `Option<Rc<T>>` or `Option<Arc<T>>` which are un-wrapped instead (see below). Specifying
+//! `strip_option` on such a field explicitly generates a method that accepts the `Box<T>` itself.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct SomeOptions {
+//!     #[builder(strip_option)]
+//!     offset: Option<f64>,
+//!
+//!     #[builder(strip_option)]
+//!     label: Option<Box<str>>,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default().offset(4.0).label("label".into());
+//!
+//! assert_eq!(options.offset, Some(4.0));
+//! assert_eq!(options.label.as_deref(), Some("label"));
+//!
+//! let options = options.offset_opt(None).label_opt(None);
+//!
+//! assert_eq!(options.offset, None);
+//! assert_eq!(options.label, None);
+//! # }
+//! ```
+//!
+//! This generates the following methods:
+//!
+//! ```
+//! # pub struct SomeOptions {
+//! #     offset: Option<f64>,
+//! # }
+//! #
+//! impl SomeOptions {
+//!     pub fn offset(self, value: f64) -> Self {
+//!         Self {
+//!             offset: Some(value),
+//!         }
+//!     }
+//!
+//!     pub fn offset_opt(self, value: Option<f64>) -> Self {
+//!         Self {
+//!             offset: value,
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Collections
+//!
+//! Fields of type `Vec` or `VecDeque` annotated with `#[builder(collection)]` get additional
+//! methods that push a single item (`push_...`), extend the collection with the items of an
+//! iterator (`extend_...`) and remove all items (`clear_...`). For `HashSet`, `BTreeSet` and
+//! `IndexSet` fields the single item method is prefixed with `insert_` instead. The names of the
+//! methods can be chosen with `#[builder(each = "...", extend = "...", clear = "...")]`. Choosing
+//! a name also turns on the additional methods so `collection` can be omitted in this case.
+//! `collection` can also be specified on the struct level in which case it applies to all
+//! collection fields. Single fields can opt out with `#[builder(collection = false)]`.
+//!
+//! The same options apply to fields of type `HashMap`, `BTreeMap` or `IndexMap`. Here the
+//! `insert_...` method takes a key and a value. Both are converted with `.into()` into the key
+//! and value types of the map.
+//!
+//! ```
+//! # use std::collections::{HashMap, HashSet};
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct SomeOptions {
+//!     #[builder(each = "plugin", extend = "plugins_from", clear = "no_plugins")]
+//!     plugins: Vec<String>,
+//!
+//!     #[builder(collection)]
+//!     ids: HashSet<u32>,
+//!
+//!     #[builder(each = "header")]
+//!     headers: HashMap<String, String>,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default()
+//!     .plugin("first".to_string())
+//!     .plugins_from(["second".to_string(), "third".to_string()])
+//!     .insert_ids(4)
+//!     .insert_ids(2)
+//!     .header("Accept", "text/html");
+//!
+//! assert_eq!(options.plugins, vec!["first", "second", "third"]);
+//! assert_eq!(options.ids, HashSet::from([2, 4]));
+//! assert_eq!(options.headers["Accept"], "text/html");
+//!
+//! let options = options.no_plugins().clear_ids();
+//!
+//! assert!(options.plugins.is_empty());
+//! assert!(options.ids.is_empty());
+//! # }
+//! ```
+//!
+//! Using one of these options on a field that isn't a collection is rejected at compile time:
+//!
+//! ```compile_fail
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct SomeOptions {
+//!     #[builder(each = "plugin")]
+//!     plugins: String,
+//! }
+//! #
+//! # fn main() {}
+//! ```
+//!
+//! ### Flags
+//!
+//! Fields of type `bool` can be annotated with `#[builder(flag)]`. Instead of a method that takes
+//! a `bool`, two methods without arguments are generated. The one named like the field sets it to
+//! `true` and the one prefixed with `no_` sets it to `false`. This can also be specified on the
+//! struct level in which case it applies to all `bool` fields. Single fields can opt out with
+//! `#[builder(flag = false)]`.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(flag)]
+//! pub struct SomeOptions {
+//!     immediate: bool,
+//!     passive: bool,
+//!
+//!     #[builder(flag = false)]
+//!     enabled: bool,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default()
+//!     .immediate()
+//!     .passive()
+//!     .no_passive()
+//!     .enabled(true);
+//!
+//! assert!(options.immediate);
+//! assert!(!options.passive);
+//! assert!(options.enabled);
+//! # }
+//! ```
+//!
+//! ### `Duration`
+//!
+//! Fields of type `std::time::Duration` annotated with `#[builder(duration)]` get two additional
+//! methods with unit suffixes. The one ending in `_ms` takes a number of milliseconds as `u64`
+//! and the one ending in `_secs` takes a number of seconds as `f64`. The latter panics if the
+//! number is negative, not finite or too large, just like `Duration::from_secs_f64`. `duration`
+//! can also be specified on the struct level in which case it applies to all `Duration` fields.
+//! Single fields can opt out with `#[builder(duration = false)]`.
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(duration)]
+//! pub struct SomeOptions {
+//!     debounce: Duration,
+//!     interval: Duration,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default()
+//!     .debounce_ms(250)
+//!     .interval_secs(1.5);
+//!
+//! assert_eq!(options.debounce, Duration::from_millis(250));
+//! assert_eq!(options.interval, Duration::from_millis(1500));
+//! # }
+//! ```
+//!
+//! ### Conditional setters
+//!
+//! With `#[builder(maybe)]` an additional method prefixed with `maybe_` is generated that takes an
+//! `Option` of whatever the normal builder method accepts. The value is only set if it is `Some`.
+//! Otherwise the current value is kept. This is handy when forwarding optional overrides.
+//! Like the other options this can be specified on the struct level and turned off for single
+//! fields with `#[builder(maybe = false)]`.
+//!
+//! For fields with `into` the `maybe_` method is not generic so that passing `None` works.
+//! It takes an `Option` of the field type, or of the inner type for fields of type `Option<T>`.
+//! The same is true for boxed trait objects like `Option<Box<dyn Fn()>>`, whose `maybe_` method
+//! takes an `Option<Box<dyn Fn()>>`.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder)]
+//! #[builder(maybe)]
+//! pub struct SomeOptions {
+//!     throttle: f64,
+//!
+//!     #[builder(into)]
+//!     name: String,
+//!
+//!     #[builder(into)]
+//!     title: Option<String>,
+//!
+//!     on_error: Option<Box<dyn Fn(String)>>,
+//! }
+//!
+//! impl Default for SomeOptions {
+//!     fn default() -> Self {
+//!         Self {
+//!             throttle: 100.0,
+//!             name: "default".to_string(),
+//!             title: None,
+//!             on_error: None,
+//!         }
+//!     }
+//! }
+//! #
+//! # fn main() {
+//! let user_throttle = Some(50.0);
+//!
+//! let options = SomeOptions::default()
+//!     .maybe_throttle(user_throttle)
+//!     .maybe_name(None)
+//!     .maybe_title(Some("title".to_string()))
+//!     .maybe_on_error(None);
+//!
+//! assert_eq!(options.throttle, 50.0);
+//! assert_eq!(options.name, "default");
+//! assert_eq!(options.title.as_deref(), Some("title"));
+//! assert!(options.on_error.is_none());
+//! # }
+//! ```
+//!
+//! ### Combinators
+//!
+//! To branch inside a chain of builder methods specify `#[builder(combinators)]` on the struct.
+//! This generates the two methods `when` and `apply`. `when` takes a condition and a closure that
+//! is only applied if the condition is `true`. `apply` always applies the closure.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(combinators)]
+//! pub struct SomeOptions {
+//!     throttle: f64,
+//!     immediate: bool,
+//! }
+//! #
+//! # fn main() {
+//! let is_mobile = true;
+//!
+//! let options = SomeOptions::default()
+//!     .throttle(100.0)
+//!     .when(is_mobile, |o| o.throttle(500.0))
+//!     .apply(|o| o.immediate(true));
+//!
+//! assert_eq!(options.throttle, 500.0);
+//! assert!(options.immediate);
+//! # }
+//! ```
+//!
+//! If several structs should share these methods through a common trait you can specify the
+//! trait with `#[builder(apply_trait = path::to::Trait)]`. Since this crate can only export macros
+//! the trait has to be defined in your own code. The derive macro implements the two methods
+//! `when` and `apply` with the signatures below for the struct.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! pub trait Apply: Sized {
+//!     fn when(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self;
+//!
+//!     fn apply(self, f: impl FnOnce(Self) -> Self) -> Self;
+//! }
+//!
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(apply_trait = Apply)]
+//! pub struct SomeOptions {
+//!     throttle: f64,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default().when(true, |o| o.throttle(500.0));
+//!
+//! assert_eq!(options.throttle, 500.0);
+//! # }
+//! ```
+//!
+//! ### Update with a closure
+//!
+//! With `#[builder(update)]` an additional method prefixed with `update_` is generated that takes
+//! a closure. The closure receives the current value of the field and returns the new one. This
+//! makes it easy to modify nested values without reading them out first. For fields with a struct
+//! generic type the closure can return a different type just like the normal builder method.
+//! This can also be specified on the struct level.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(update)]
+//! pub struct SomeOptions<T> {
+//!     plugins: Vec<String>,
+//!     value: T,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::<u32>::default()
+//!     .update_plugins(|mut plugins| {
+//!         plugins.push("extra".to_string());
+//!         plugins
+//!     })
+//!     .update_value(|value| format!("{}", value + 1));
+//!
+//! assert_eq!(options.plugins, vec!["extra"]);
+//! assert_eq!(options.value, "1");
+//! # }
+//! ```
+//!
+//! ### Reset
+//!
+//! With `#[builder(reset)]` an additional method prefixed with `reset_` is generated that sets the
+//! field back to its value in `Self::default()`. If the default value of a single field should be
+//! something else you can provide it with `#[builder(default = ...)]` which also generates the
+//! `reset_` method. `reset` can also be specified on the struct level.
+//!
+//! The default value can be any expression. A string literal is converted with `.into()` so
+//! `#[builder(default = "fallback")]` works for `String` as well as for `&'static str` fields.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder)]
+//! #[builder(reset)]
+//! pub struct SomeOptions {
+//!     throttle: f64,
+//!
+//!     #[builder(default = "fallback")]
+//!     name: String,
+//!
+//!     #[builder(default = "static")]
+//!     label: &'static str,
+//! }
+//!
+//! impl Default for SomeOptions {
+//!     fn default() -> Self {
+//!         Self {
+//!             throttle: 100.0,
+//!             name: "default".to_string(),
+//!             label: "default",
+//!         }
+//!     }
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default()
+//!     .throttle(500.0)
+//!     .name("custom".to_string())
+//!     .label("custom")
+//!     .reset_throttle()
+//!     .reset_name()
+//!     .reset_label();
+//!
+//! assert_eq!(options.throttle, 100.0);
+//! assert_eq!(options.name, "fallback");
+//! assert_eq!(options.label, "static");
+//! # }
+//! ```
+//!
+//! ### In place setters
+//!
+//! All builder methods consume `self` and return a new `Self`. If you need to modify options that
+//! are stored somewhere else, like inside another struct or a `RefCell`, you can specify
+//! `#[builder(in_place)]`. This generates additional methods prefixed with `set_` that take
+//! `&mut self` and return `&mut Self`. They accept the same arguments as the normal builder
+//! methods. This can be specified on the struct level or for single fields.
+//!
+//! ```
+//! # use std::cell::RefCell;
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(in_place)]
+//! pub struct SomeOptions {
+//!     throttle: f64,
+//!
+//!     #[builder(into)]
+//!     name: String,
+//!
+//!     on_change: Option<Box<dyn Fn(f64)>>,
+//! }
+//! #
+//! # fn main() {
+//! let options = RefCell::new(SomeOptions::default());
+//!
+//! options
+//!     .borrow_mut()
+//!     .set_throttle(500.0)
+//!     .set_name("Hello")
+//!     .set_on_change(|value| println!("{value}"));
+//!
+//! assert_eq!(options.borrow().throttle, 500.0);
+//! assert_eq!(options.borrow().name, "Hello");
+//! assert!(options.borrow().on_change.is_some());
+//! # }
+//! ```
+//!
+//! ### Non-consuming setters
+//!
+//! For structs that implement `Clone` you can specify `#[builder(with)]` to generate additional
+//! methods prefixed with `with_` that take `&self`. They return a clone of `self` with the field
+//! changed. This way one base configuration can be reused to create many variants.
+//! This can be specified on the struct level or for single fields.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default, Clone)]
+//! #[builder(with)]
+//! pub struct SomeOptions {
+//!     throttle: f64,
+//!
+//!     #[builder(into)]
+//!     name: String,
+//! }
+//! #
+//! # fn main() {
+//! let base = SomeOptions::default().throttle(100.0);
+//!
+//! let fast = base.with_throttle(10.0);
+//! let named = base.with_name("named");
+//!
+//! assert_eq!(base.throttle, 100.0);
+//! assert_eq!(fast.throttle, 10.0);
+//! assert_eq!(named.name, "named");
+//! assert_eq!(named.throttle, 100.0);
+//! # }
+//! ```
+//!
+//! ### Shared options
+//!
+//! If your options are stored in an `Rc` or `Arc` for cheap sharing you can specify
+//! `#[builder(shared)]` on the struct. This generates an extension trait named after the struct
+//! with the suffix `SharedExt`. It is implemented for `Rc<Struct>` and `Arc<Struct>` and provides
+//! the same builder methods. They use `make_mut` to modify the value which clones it only if it is
+//! shared. Therefore the struct has to implement `Clone`.
+//!
+//! ```
+//! # use std::rc::Rc;
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default, Clone)]
+//! #[builder(shared)]
+//! pub struct SomeOptions {
+//!     throttle: f64,
+//!
+//!     #[builder(flag)]
+//!     immediate: bool,
+//! }
+//! #
+//! # fn main() {
+//! let options = Rc::new(SomeOptions::default());
+//!
+//! let changed = Rc::clone(&options).throttle(500.0).immediate();
+//!
+//! assert_eq!(options.throttle, 0.0);
+//! assert_eq!(changed.throttle, 500.0);
+//! assert!(changed.immediate);
+//! # }
+//! ```
+//!
+//! ### Getters
+//!
+//! To inspect a configured struct whose fields are private you can generate getters with
+//! `#[builder(get)]`. The getter is prefixed with `get_` and returns a reference to the field.
+//! `#[builder(get_mut)]` generates a getter with the suffix `_mut` that returns a mutable reference.
+//! For fields of a `Copy` type you can use `#[builder(get_copy)]` which returns the value instead
+//! of a reference. `get` and `get_mut` can also be specified on the struct level. Getters are
+//! generated for skipped fields as well.
+//!
+//! ```
+//! mod options {
+//!     # use default_struct_builder::DefaultBuilder;
+//!     #
+//!     #[derive(DefaultBuilder, Default)]
+//!     #[builder(get)]
+//!     pub struct SomeOptions {
+//!         name: String,
+//!
+//!         #[builder(get_copy)]
+//!         throttle: f64,
+//!
+//!         #[builder(skip, get_mut)]
+//!         counter: u32,
+//!     }
+//! }
+//! #
+//! # fn main() {
+//! use options::SomeOptions;
+//!
+//! let mut options = SomeOptions::default().name("Hello".to_string()).throttle(4.0);
+//!
+//! *options.get_counter_mut() += 1;
+//!
+//! assert_eq!(options.get_name(), "Hello");
+//! assert_eq!(options.get_throttle(), 4.0);
+//! assert_eq!(*options.get_counter(), 1);
+//! # }
+//! ```
+//!
+//! ### Validation
+//!
+//! With `#[builder(validate = path::to::function)]` the value of a field is validated whenever it
+//! is set. The validator receives a reference to the new field value and returns a
+//! `Result<(), E>`. An additional method prefixed with `try_` is generated that returns the error
+//! instead of setting the value. Its error type is `String` by default and can be changed with
+//! `#[builder(error = path::to::Error)]`. The validator's error is converted with `?`.
+//!
+//! The normal builder method panics if the validation fails. So do the `_opt`, `maybe_`, `set_`,
+//! `with_` and `update_` methods, the methods of the `SharedExt` and `FlattenExt` traits and
+//! setter groups. The `reset_` and `clear_` methods, the collection and `Duration` methods and
+//! the `_with` method don't validate the field.
+//!
+//! If you'd rather not have panicking setters you can remove them with
+//! `#[builder(on_invalid = "remove")]`. This removes all of the panicking methods listed above.
+//! Such a field can't be part of a `setter_group`.
+//!
+//! Validation can't be combined with `flag`.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! fn non_negative(value: &f64) -> Result<(), String> {
+//!     if *value < 0.0 {
+//!         Err(format!("{value} is negative"))
+//!     } else {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! pub struct InvalidUrl;
+//!
+//! fn valid_url(value: &String) -> Result<(), InvalidUrl> {
+//!     if value.starts_with("https://") {
+//!         Ok(())
+//!     } else {
+//!         Err(InvalidUrl)
+//!     }
+//! }
+//!
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct SomeOptions {
+//!     #[builder(validate = non_negative, update)]
+//!     throttle: f64,
+//!
+//!     #[builder(into, validate = valid_url, error = InvalidUrl, on_invalid = "remove")]
+//!     url: String,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default().throttle(100.0);
+//!
+//! assert_eq!(options.throttle, 100.0);
+//! assert!(options.try_throttle(-1.0).is_err());
+//!
+//! let result = std::panic::catch_unwind(|| {
+//!     SomeOptions::default().update_throttle(|throttle| throttle - 1.0)
+//! });
+//!
+//! assert!(result.is_err());
+//!
+//! let options = SomeOptions::default().try_url("https://example.com").unwrap();
+//!
+//! assert_eq!(options.url, "https://example.com");
+//! assert_eq!(options.try_url("ftp://example.com").err(), Some(InvalidUrl));
+//! # }
+//! ```
+//!
+//! With `on_invalid = "remove"` there is no `set_url` method:
+//!
+//! ```compile_fail
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! # fn valid_url(value: &String) -> Result<(), String> {
+//! #     Ok(())
+//! # }
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(in_place)]
+//! pub struct SomeOptions {
+//!     #[builder(validate = valid_url, on_invalid = "remove")]
+//!     url: String,
+//! }
+//! #
+//! # fn main() {
+//! let mut options = SomeOptions::default();
+//! options.set_url("ftp://example.com".to_string());
+//! # }
+//! ```
+//!
+//! ### Ranges
+//!
+//! Numeric fields can be constrained to a range with `#[builder(range = 0.0..=1.0)]`. What happens
+//! when a value outside of this range is set can be configured with `on_out_of_range`:
+//!
+//! - `"panic"` (default): the builder method panics.
+//! - `"debug_assert"`: the builder method panics only in debug builds.
+//! - `"clamp"`: the value is clamped to the range. This requires an inclusive range.
+//!
+//! For fields of type `Option<T>` the range applies to the inner value if it is `Some`. The range
+//! is also enforced by the `_opt`, `maybe_`, `set_`, `with_` and `update_` methods.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct SomeOptions {
+//!     #[builder(range = 0.0..=1.0, on_out_of_range = "clamp")]
+//!     ratio: f64,
+//!
+//!     #[builder(range = 1.., update)]
+//!     retries: u32,
+//!
+//!     #[builder(range = 0.0..100.0, on_out_of_range = "debug_assert")]
+//!     percentage: f64,
+//!
+//!     #[builder(strip_option, range = 0.0..=1.0, on_out_of_range = "clamp")]
+//!     threshold: Option<f64>,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default()
+//!     .ratio(1.5)
+//!     .retries(3)
+//!     .percentage(50.0)
+//!     .threshold(-0.5);
+//!
+//! assert_eq!(options.ratio, 1.0);
+//! assert_eq!(options.retries, 3);
+//! assert_eq!(options.percentage, 50.0);
+//! assert_eq!(options.threshold, Some(0.0));
+//! assert_eq!(options.threshold_opt(Some(7.0)).threshold, Some(1.0));
+//!
+//! let result = std::panic::catch_unwind(|| SomeOptions::default().update_retries(|_| 0));
+//!
+//! assert!(result.is_err());
+//!
+//! let result = std::panic::catch_unwind(|| SomeOptions::default().retries(0));
+//!
+//! assert!(result.is_err());
+//!
+//! let result = std::panic::catch_unwind(|| SomeOptions::default().percentage(100.0));
+//!
+//! assert_eq!(result.is_err(), cfg!(debug_assertions));
+//! # }
+//! ```
+//!
+//! Clamping to a half-open range is rejected at compile time:
+//!
+//! ```compile_fail
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct SomeOptions {
+//!     #[builder(range = 0.0..1.0, on_out_of_range = "clamp")]
+//!     ratio: f64,
+//! }
+//! #
+//! # fn main() {}
+//! ```
+//!
+//! ### Post-set hook
+//!
+//! With `#[builder(on_set = Self::after_set)]` on the struct the given function is called after
+//! every generated method that changes a field. It receives the name of the field that was set.
+//! It can be any path to a method or a free function that takes `&mut Self` and the field name as
+//! a `&'static str`. This is useful to keep derived values consistent or to log configuration
+//! changes.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(on_set = Self::after_set)]
+//! pub struct SomeOptions {
+//!     width: u32,
+//!     height: u32,
+//!
+//!     #[builder(skip)]
+//!     area: u32,
+//! }
+//!
+//! impl SomeOptions {
+//!     fn after_set(&mut self, field: &'static str) {
+//!         if field == "width" || field == "height" {
+//!             self.area = self.width * self.height;
+//!         }
+//!     }
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default().width(3).height(4);
+//!
+//! assert_eq!(options.area, 12);
+//! # }
+//! ```
+//!
+//! A free function works the same way:
+//!
+//! ```
+//! # use std::cell::RefCell;
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! thread_local! {
+//!     static CHANGES: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+//! }
+//!
+//! fn log_change(_options: &mut LoggedOptions, field: &'static str) {
+//!     CHANGES.with(|changes| changes.borrow_mut().push(field));
+//! }
+//!
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(on_set = log_change, in_place)]
+//! pub struct LoggedOptions {
+//!     width: u32,
+//!     height: u32,
+//! }
+//! #
+//! # fn main() {
+//! let mut options = LoggedOptions::default().width(3);
+//! options.set_height(4);
+//!
+//! CHANGES.with(|changes| assert_eq!(*changes.borrow(), ["width", "height"]));
+//! # }
+//! ```
+//!
+//! On generic structs the hook is also called by the methods that change the type of a generic
+//! field. `Self` in the path then refers to the struct with the new type.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(on_set = Self::count_change)]
+//! pub struct GenericOptions<T> {
+//!     value: T,
+//!     other: u32,
+//!
+//!     #[builder(skip)]
+//!     changes: u32,
+//! }
+//!
+//! impl<T> GenericOptions<T> {
+//!     fn count_change(&mut self, _field: &'static str) {
+//!         self.changes += 1;
+//!     }
+//! }
+//! #
+//! # fn main() {
+//! let options = GenericOptions::<u8>::default().value("text").other(3);
+//!
+//! assert_eq!(options.value, "text");
+//! assert_eq!(options.changes, 2);
+//! # }
+//! ```
+//!
+//! ### Exclusive groups
+//!
+//! Several `Option` fields can be put into a group with `#[builder(group = "...")]`. For every
+//! group a method with the suffix `_kind` is generated that returns the name of the field of the
+//! group that is set. If all fields of the group are marked as `exclusive` then setting one field
+//! of the group through a generated method sets all other fields of the group to `None`. Marking
+//! only some fields of a group as `exclusive` is an error, as is `exclusive` on a field without a
+//! `group`.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct LoaderOptions {
+//!     #[builder(group = "source", exclusive, strip_option)]
+//!     url: Option<String>,
+//!
+//!     #[builder(group = "source", exclusive, strip_option)]
+//!     file: Option<String>,
+//!
+//!     #[builder(group = "source", exclusive, strip_option)]
+//!     bytes: Option<Vec<u8>>,
+//! }
+//! #
+//! # fn main() {
+//! let options = LoaderOptions::default()
+//!     .url("https://example.com".to_string())
+//!     .file("data.bin".to_string());
+//!
+//! assert_eq!(options.url, None);
+//! assert_eq!(options.file.as_deref(), Some("data.bin"));
+//! assert_eq!(options.source_kind(), Some("file"));
+//! # }
+//! ```
+//!
+//! ### Setter groups
+//!
+//! Fields that are usually set together can get a combined setter with
+//! `#[builder(setter_group(name = "...", fields(...)))]` on the struct. The generated method takes
+//! one argument per field in the given order and converts each of them like the single setter of
+//! that field would. The attribute can be repeated to generate several groups.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! #[builder(setter_group(name = "size", fields(width, height)))]
+//! pub struct SomeOptions {
+//!     width: u32,
+//!     height: u32,
+//!
+//!     #[builder(into)]
+//!     title: Option<String>,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default().size(640, 480).title("Window");
+//!
+//! assert_eq!(options.width, 640);
+//! assert_eq!(options.height, 480);
+//! assert_eq!(options.title.as_deref(), Some("Window"));
+//! # }
+//! ```
+//!
+//! ### Nested options
+//!
+//! If a field is itself an options struct that derives `DefaultBuilder` you can mark it with
+//! `#[builder(nested)]`. This generates a method with the suffix `_with` that takes a closure
+//! which receives the current value and returns the new one. That way the builder methods of
+//! the nested struct can be used without naming its type. For an `Option` field the closure
+//! receives the current value or the default value if the field is `None`.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct RetryOptions {
+//!     max: u32,
+//!     backoff: f64,
+//! }
+//!
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct RequestOptions {
+//!     #[builder(nested)]
+//!     retry: RetryOptions,
+//!
+//!     #[builder(nested)]
+//!     fallback_retry: Option<RetryOptions>,
+//! }
+//! #
+//! # fn main() {
+//! let options = RequestOptions::default()
+//!     .retry_with(|r| r.max(3).backoff(2.0))
+//!     .fallback_retry_with(|r| r.max(1));
+//!
+//! assert_eq!(options.retry.max, 3);
+//! assert_eq!(options.retry.backoff, 2.0);
+//! assert_eq!(options.fallback_retry.map(|r| r.max), Some(1));
+//! # }
+//! ```
+//!
+//! ### Flatten
+//!
+//! Options that are shared by several option structs can be put into their own struct and embedded
+//! with `#[builder(flatten)]`. The outer struct then gets forwarding setters for the fields of the
+//! embedded struct so callers can use one flat API. The forwarding setters accept the same
+//! arguments as the setters of the embedded struct. With `#[builder(flatten = "net")]` their names
+//! are prefixed with `net_` which also allows to embed the same struct several times.
+//!
+//! Since a derive macro cannot look into other types, the embedded struct has to opt in with
+//! `#[builder(flattenable)]`. This generates the trait `{Struct}FlattenExt` and a macro named like
+//! the struct which are used by the derive of the outer struct. Both have to be reachable where the
+//! outer struct is defined. Either import both or refer to the embedded struct by its path. As
+//! macros generated this way can't be exported, flattening only works within the same crate.
+//!
+//! A forwarding setter calls the `on_set` hook of the embedded struct with the name of the inner
+//! field and the hook of the outer struct with the name of the flattened field.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! mod common {
+//!     # use default_struct_builder::DefaultBuilder;
+//!     #
+//!     #[derive(DefaultBuilder, Default)]
+//!     #[builder(flattenable)]
+//!     pub struct CommonOptions {
+//!         pub verbose: bool,
+//!
+//!         #[builder(into)]
+//!         pub name: Option<String>,
+//!     }
+//!
+//!     #[derive(DefaultBuilder, Default)]
+//!     #[builder(flattenable)]
+//!     pub struct NetworkOptions {
+//!         pub port: u16,
+//!     }
+//! }
+//!
+//! use common::{CommonOptions, CommonOptionsFlattenExt};
+//!
+//! #[derive(DefaultBuilder, Default)]
+//! pub struct ServerOptions {
+//!     #[builder(flatten)]
+//!     common: CommonOptions,
+//!
+//!     #[builder(flatten = "public")]
+//!     public: common::NetworkOptions,
+//!
+//!     #[builder(flatten = "admin")]
+//!     admin: common::NetworkOptions,
+//!
+//!     workers: usize,
+//! }
+//! #
+//! # fn main() {
+//! let options = ServerOptions::default()
+//!     .verbose(true)
+//!     .name("server")
+//!     .public_port(80)
+//!     .admin_port(8080)
+//!     .workers(4);
+//!
+//! assert!(options.common.verbose);
+//! assert_eq!(options.common.name.as_deref(), Some("server"));
+//! assert_eq!(options.public.port, 80);
+//! assert_eq!(options.admin.port, 8080);
+//! assert_eq!(options.workers, 4);
+//! # }
+//! ```
+//!
 //! ## How it works
 //!
 //! The derive macro generates the following code:
@@ -266,6 +1254,258 @@
 //! }
 //! ```
 //!
+//! ### `Option` of `Box`, `Rc` and `Arc`
+//!
+//! The same un-wrapping is done for fields of type `Option<Box<T>>`, `Option<Rc<T>>` and
+//! `Option<Arc<T>>`. The builder method accepts the inner type and wraps it in `Some(...)`.
+//! To set such a field back to `None` an additional method prefixed with `clear_` is generated.
+//!
+//! Wrappers of unsized types like `str`, slices, `Path`, `OsStr` and `CStr` can't be un-wrapped
+//! because their value can't be passed to a method. Fields like `Option<Arc<str>>` get a normal
+//! builder method instead.
+//!
+//! ```
+//! # use std::rc::Rc;
+//! # use std::sync::Arc;
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! struct SomeOptions {
+//!     on_error: Option<Box<dyn Fn(String)>>,
+//!     name: Option<Rc<String>>,
+//!     label: Option<Arc<str>>,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default()
+//!     .on_error(|err| println!("{err}"))
+//!     .name("Hello".to_string())
+//!     .label(Some("label".into()));
+//!
+//! assert!(options.on_error.is_some());
+//! assert_eq!(options.name.as_deref().map(String::as_str), Some("Hello"));
+//! assert_eq!(options.label.as_deref(), Some("label"));
+//!
+//! let options = options.clear_on_error();
+//!
+//! assert!(options.on_error.is_none());
+//! # }
+//! ```
+//!
+//! This will generate the following code:
+//!
+//! ```
+//! # use std::rc::Rc;
+//! #
+//! # struct SomeOptions {
+//! #     on_error: Option<Box<dyn Fn(String)>>,
+//! #     name: Option<Rc<String>>,
+//! # }
+//! #
+//! impl SomeOptions {
+//!     pub fn on_error(self, value: impl Fn(String) + 'static) -> Self {
+//!         Self {
+//!             on_error: Some(Box::new(value)),
+//!             ..self
+//!         }
+//!     }
+//!
+//!     /// Sets `on_error` to `None`.
+//!     pub fn clear_on_error(self) -> Self {
+//!         Self {
+//!             on_error: None,
+//!             ..self
+//!         }
+//!     }
+//!
+//!     pub fn name(self, value: String) -> Self {
+//!         Self {
+//!             name: Some(Rc::new(value)),
+//!             ..self
+//!         }
+//!     }
+//!
+//!     /// Sets `name` to `None`.
+//!     pub fn clear_name(self) -> Self {
+//!         Self {
+//!             name: None,
+//!             ..self
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Nested wrappers
+//!
+//! Shared mutable state and pinned values are un-wrapped as well. Fields of type
+//! `Rc<RefCell<T>>`, `Arc<Mutex<T>>`, `Arc<RwLock<T>>` (or any other combination of
+//! `Box`, `Rc` or `Arc` with `RefCell`, `Cell`, `Mutex` or `RwLock`) get a builder method that
+//! accepts `T`. Fields of type `Pin<Box<T>>` are set with `Box::pin(value)`, which makes it easy to
+//! store futures.
+//!
+//! ```
+//! # use std::cell::RefCell;
+//! # use std::future::Future;
+//! # use std::pin::Pin;
+//! # use std::rc::Rc;
+//! # use std::sync::{Arc, Mutex};
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder)]
+//! struct SomeOptions {
+//!     counter: Rc<RefCell<u32>>,
+//!     shared: Arc<Mutex<String>>,
+//!     task: Pin<Box<dyn Future<Output = u32>>>,
+//! }
+//! #
+//! # fn main() {
+//! # let options = SomeOptions {
+//! #     counter: Default::default(),
+//! #     shared: Default::default(),
+//! #     task: Box::pin(async { 0 }),
+//! # };
+//! let options = options
+//!     .counter(42)
+//!     .shared("Hello".to_string())
+//!     .task(async { 42 });
+//!
+//! assert_eq!(*options.counter.borrow(), 42);
+//! assert_eq!(*options.shared.lock().unwrap(), "Hello");
+//! # }
+//! ```
+//!
+//! This will generate the following code:
+//!
+//! ```
+//! # use std::cell::RefCell;
+//! # use std::future::Future;
+//! # use std::pin::Pin;
+//! # use std::rc::Rc;
+//! # use std::sync::{Arc, Mutex};
+//! #
+//! # struct SomeOptions {
+//! #     counter: Rc<RefCell<u32>>,
+//! #     shared: Arc<Mutex<String>>,
+//! #     task: Pin<Box<dyn Future<Output = u32>>>,
+//! # }
+//! #
+//! impl SomeOptions {
+//!     pub fn counter(self, value: u32) -> Self {
+//!         Self {
+//!             counter: Rc::new(RefCell::new(value)),
+//!             ..self
+//!         }
+//!     }
+//!
+//!     pub fn shared(self, value: String) -> Self {
+//!         Self {
+//!             shared: Arc::new(Mutex::new(value)),
+//!             ..self
+//!         }
+//!     }
+//!
+//!     pub fn task(self, value: impl Future<Output = u32> + 'static) -> Self {
+//!         Self {
+//!             task: Box::pin(value),
+//!             ..self
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### Trait object bounds
+//!
+//! When converting a trait object into an `impl Trait` argument, lifetime bounds of the trait
+//! object are kept. Only if there is no lifetime bound `'static` is added. Additional auto trait
+//! bounds like `Send` or `Sync` and higher-ranked trait bounds are passed on as they are.
+//!
+//! ```
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder)]
+//! struct SomeOptions<'a> {
+//!     formatter: Box<dyn Fn(&str) -> String + 'a>,
+//!     parser: Box<dyn for<'x> Fn(&'x str) -> &'x str + Send + Sync>,
+//! }
+//! #
+//! # fn main() {
+//! # let prefix = String::from("> ");
+//! # let options = SomeOptions {
+//! #     formatter: Box::new(|s| s.to_string()),
+//! #     parser: Box::new(|s| s),
+//! # }
+//! # .formatter(|s| format!("{prefix}{s}"))
+//! # .parser(|s| s.trim());
+//! # assert_eq!((options.formatter)("a"), "> a");
+//! # assert_eq!((options.parser)(" a "), "a");
+//! # }
+//! ```
+//!
+//! This will generate the following code:
+//!
+//! ```
+//! # struct SomeOptions<'a> {
+//! #     formatter: Box<dyn Fn(&str) -> String + 'a>,
+//! #     parser: Box<dyn for<'x> Fn(&'x str) -> &'x str + Send + Sync>,
+//! # }
+//! #
+//! impl<'a> SomeOptions<'a> {
+//!     pub fn formatter(self, value: impl Fn(&str) -> String + 'a) -> Self {
+//!         Self {
+//!             formatter: Box::new(value),
+//!             ..self
+//!         }
+//!     }
+//!
+//!     pub fn parser(
+//!         self,
+//!         value: impl for<'x> Fn(&'x str) -> &'x str + Send + Sync + 'static,
+//!     ) -> Self {
+//!         Self {
+//!             parser: Box::new(value),
+//!             ..self
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ### `Cow`, `PathBuf` and `OsString`
+//!
+//! Fields of type `Cow`, `PathBuf` or `OsString` behave as if `#[builder(into)]` was specified.
+//! This way they accept borrowed as well as owned values.
+//!
+//! ```
+//! # use std::borrow::Cow;
+//! # use std::ffi::OsString;
+//! # use std::path::{Path, PathBuf};
+//! # use default_struct_builder::DefaultBuilder;
+//! #
+//! #[derive(DefaultBuilder, Default)]
+//! struct SomeOptions<'a> {
+//!     label: Cow<'a, str>,
+//!     data: Cow<'a, [u8]>,
+//!     path: PathBuf,
+//!     program: OsString,
+//! }
+//! #
+//! # fn main() {
+//! let options = SomeOptions::default()
+//!     .label("borrowed")
+//!     .data(vec![1, 2, 3])
+//!     .path(Path::new("/tmp"))
+//!     .program("ls");
+//!
+//! assert_eq!(options.label, "borrowed");
+//! assert_eq!(options.data, Cow::Borrowed(&[1_u8, 2, 3][..]));
+//! assert_eq!(options.path, PathBuf::from("/tmp"));
+//! assert_eq!(options.program, "ls");
+//!
+//! let options = options.label(String::from("owned"));
+//!
+//! assert_eq!(options.label, "owned");
+//! # }
+//! ```
+//!
 //!
 //! ## Related Work
 //!
@@ -273,9 +1513,11 @@
 //! [`derive_builder` crate](https://github.com/colin-kiegel/rust-derive-builder).
 
 mod builder;
+mod flatten;
 
 use builder::DefaultBuilderDeriveInput;
 use darling::FromDeriveInput;
+use flatten::FlattenInput;
 use proc_macro::TokenStream;
 use quote::ToTokens;
 use syn::parse_macro_input;
@@ -290,3 +1532,12 @@ pub fn derive_builder(input: TokenStream) -> TokenStream {
     };
     stream.into()
 }
+
+/// Generates the forwarding setters for a `#[builder(flatten)]` field. It is invoked through the
+/// macro that is generated for structs with `#[builder(flattenable)]`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __flatten(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as FlattenInput);
+    input.forwarding_methods().into()
+}